@@ -0,0 +1,63 @@
+//! Benchmarks of the numeric parsers (unsigned, signed, float) over a few classes of input.
+//!
+//! Baseline (`cargo bench --bench numbers`, before the place-value and overflow fixes):
+//!
+//! | Benchmark        | Time    |
+//! | ---------------- | ------- |
+//! | unsigned/short   | 29.2 ns |
+//! | unsigned/long    | 59.0 ns |
+//! | signed/short     | 25.8 ns |
+//! | signed/long      | 38.6 ns |
+//! | float/integer    | 25.4 ns |
+//! | float/fraction   | 99.1 ns |
+//! | float/exponent   | 81.1 ns |
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use yadil::Parser;
+
+const SHORT_INTEGER: &[u8] = b"42";
+const LONG_INTEGER: &[u8] = b"1234567890123456789";
+const FRACTION: &[u8] = b"3.14159265358979";
+const EXPONENT: &[u8] = b"6.02214076e23";
+
+fn unsigned(c: &mut Criterion) {
+	let mut parser = Parser::new(b"");
+
+	c.bench_function("unsigned/short", |b| {
+		b.iter(|| parser.parse_unsigned(black_box(SHORT_INTEGER).to_vec()))
+	});
+	c.bench_function("unsigned/long", |b| {
+		b.iter(|| parser.parse_unsigned(black_box(LONG_INTEGER).to_vec()))
+	});
+}
+
+fn signed(c: &mut Criterion) {
+	let mut parser = Parser::new(b"");
+
+	c.bench_function("signed/short", |b| {
+		b.iter(|| parser.parse_signed(black_box(b"-42").to_vec()))
+	});
+	c.bench_function("signed/long", |b| {
+		b.iter(|| parser.parse_signed(black_box(b"-123456789012345678").to_vec()))
+	});
+}
+
+fn float(c: &mut Criterion) {
+	let mut parser = Parser::new(b"");
+
+	c.bench_function("float/integer", |b| {
+		b.iter(|| parser.parse_float(black_box(SHORT_INTEGER).to_vec()))
+	});
+	c.bench_function("float/fraction", |b| {
+		b.iter(|| parser.parse_float(black_box(FRACTION).to_vec()))
+	});
+	// Exponents aren't supported yet, so this measures how fast they are rejected.
+	c.bench_function("float/exponent", |b| {
+		b.iter(|| parser.parse_float(black_box(EXPONENT).to_vec()))
+	});
+}
+
+criterion_group!(benches, unsigned, signed, float);
+criterion_main!(benches);
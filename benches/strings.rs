@@ -0,0 +1,42 @@
+//! Benchmarks of parsing a string-heavy message, copying every string or borrowing them.
+//!
+//! Baseline (`cargo bench --bench strings`, 1000 string assignments):
+//!
+//! | Benchmark        | Time     |
+//! | ---------------- | -------- |
+//! | strings/owned    | 277.7 µs |
+//! | strings/borrowed | 92.0 µs  |
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use yadil::Parser;
+
+fn message() -> Vec<u8> {
+	(0..1000)
+		.flat_map(|i| format!("s@key{i}=some fairly long string value number {i};").into_bytes())
+		.collect()
+}
+
+fn strings(c: &mut Criterion) {
+	let input = message();
+
+	c.bench_function("strings/owned", |b| {
+		b.iter(|| Parser::new(black_box(&input)).exprs().count())
+	});
+	c.bench_function("strings/borrowed", |b| {
+		b.iter(|| {
+			let mut parser = Parser::new(black_box(&input));
+			let mut count = 0;
+
+			while let Ok(Some(_)) = parser.next_borrowed() {
+				count += 1;
+			}
+
+			count
+		})
+	});
+}
+
+criterion_group!(benches, strings);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+//! Parsing into a caller-provided bump arena, so that a long-running server can reuse the memory
+//! of its messages.
+
+use std::collections::HashMap;
+
+use bumpalo::Bump;
+
+use crate::{Parser, Result, Value};
+
+/// A value allocated in a [`Bump`] arena, living as long as the arena isn't reset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArenaValue<'a> {
+	String(&'a str),
+	Unsigned(usize),
+	Signed(isize),
+	Float(f64),
+	Bool(bool),
+	#[cfg(feature = "bytes")]
+	Byte(u8),
+	#[cfg(feature = "bytes")]
+	Bytes(&'a [u8]),
+	#[cfg(feature = "containers")]
+	List(&'a [ArenaValue<'a>]),
+	/// Entries in the order they were written.
+	#[cfg(feature = "containers")]
+	Map(&'a [(&'a [u8], ArenaValue<'a>)]),
+}
+
+impl ArenaValue<'_> {
+	/// Copies the value out of the arena.
+	pub fn to_value(&self) -> Value {
+		match *self {
+			ArenaValue::String(string) => Value::String(string.to_string()),
+			ArenaValue::Unsigned(unsigned) => Value::Unsigned(unsigned),
+			ArenaValue::Signed(signed) => Value::Signed(signed),
+			ArenaValue::Float(float) => Value::Float(float),
+			ArenaValue::Bool(bool) => Value::Bool(bool),
+			#[cfg(feature = "bytes")]
+			ArenaValue::Byte(byte) => Value::Byte(byte),
+			#[cfg(feature = "bytes")]
+			ArenaValue::Bytes(bytes) => Value::Bytes(bytes.to_vec()),
+			#[cfg(feature = "containers")]
+			ArenaValue::List(list) => Value::List(list.iter().map(ArenaValue::to_value).collect()),
+			#[cfg(feature = "containers")]
+			ArenaValue::Map(map) => Value::Map(
+				map.iter()
+					.map(|(key, value)| (key.to_vec(), value.to_value()))
+					.collect(),
+			),
+		}
+	}
+}
+
+/// A message allocated in a [`Bump`] arena, with its entries in the order they were written.
+///
+/// Its entries are indexed by key on the heap, so looking one up doesn't walk them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArenaMessage<'a> {
+	entries: &'a [(&'a [u8], ArenaValue<'a>)],
+	index: HashMap<&'a [u8], usize>,
+}
+
+impl<'a> ArenaMessage<'a> {
+	pub(crate) fn new(
+		entries: &'a [(&'a [u8], ArenaValue<'a>)],
+		index: HashMap<&'a [u8], usize>,
+	) -> ArenaMessage<'a> {
+		ArenaMessage { entries, index }
+	}
+
+	/// Returns the value of `key`.
+	pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&'a ArenaValue<'a>> {
+		let entries = self.entries;
+		self.index.get(key.as_ref()).map(|&i| &entries[i].1)
+	}
+
+	/// The entries of the message, in the order they were written.
+	pub fn entries(&self) -> &'a [(&'a [u8], ArenaValue<'a>)] {
+		self.entries
+	}
+}
+
+/// Parse a YADIL message into `arena`. Resetting the arena between messages reuses its memory.
+///
+/// Strings, lists and maps are allocated in the arena as they're parsed, so the heap only holds
+/// the index of the message's keys and the assignment being read. A key assigned more than once
+/// keeps its last value, as with [`crate::parse`].
+pub fn parse_in<'a>(input: &[u8], arena: &'a Bump) -> Result<ArenaMessage<'a>> {
+	Parser::new(input).parse_in(arena)
+}
@@ -0,0 +1,231 @@
+//! Compact, self-describing binary encoding of messages.
+//!
+//! Every value starts with a 1-byte tag, followed by its payload. Integers and lengths are
+//! LEB128 varints (zigzag-encoded for signed integers), and floats are 8 little-endian bytes.
+//! A message is encoded as the entry count followed by each key and value, like a map.
+
+use crate::{Error, ErrorKind, Map, Message, Result, Value};
+
+const STRING: u8 = 0;
+const UNSIGNED: u8 = 1;
+const SIGNED: u8 = 2;
+const FLOAT: u8 = 3;
+const FALSE: u8 = 4;
+const TRUE: u8 = 5;
+const BYTES: u8 = 6;
+const LIST: u8 = 7;
+const MAP: u8 = 8;
+const BYTE: u8 = 9;
+
+/// How deep lists and maps can be nested before decoding gives up.
+const MAX_DEPTH: usize = 128;
+
+/// Encode a message in the binary format.
+pub fn to_binary(message: &Message) -> Vec<u8> {
+	let mut out = vec![];
+	write_map(&message.0, &mut out);
+	out
+}
+
+/// Decode a message from the binary format.
+pub fn from_binary(input: &[u8]) -> Result<Message> {
+	let mut decoder = Decoder { input, index: 0 };
+	let map = decoder.map(0)?;
+
+	if decoder.index != input.len() {
+		return Err(decoder.error(
+			ErrorKind::UnexpectedChar,
+			"Trailing bytes after the message",
+		));
+	}
+
+	Ok(Message(map))
+}
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+	while value >= 0x80 {
+		out.push(value as u8 | 0x80);
+		value >>= 7;
+	}
+
+	out.push(value as u8);
+}
+
+fn write_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+	write_varint(bytes.len() as u64, out);
+	out.extend_from_slice(bytes);
+}
+
+fn write_map(map: &Map, out: &mut Vec<u8>) {
+	write_varint(map.len() as u64, out);
+
+	for (key, value) in map {
+		write_bytes(key, out);
+		write_value(value, out);
+	}
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) {
+	match value {
+		Value::String(string) => {
+			out.push(STRING);
+			write_bytes(string.as_bytes(), out);
+		}
+		Value::Unsigned(unsigned) => {
+			out.push(UNSIGNED);
+			write_varint(*unsigned as u64, out);
+		}
+		Value::Signed(signed) => {
+			let signed = *signed as i64;
+			out.push(SIGNED);
+			write_varint(((signed << 1) ^ (signed >> 63)) as u64, out);
+		}
+		Value::Float(float) => {
+			out.push(FLOAT);
+			out.extend_from_slice(&float.to_le_bytes());
+		}
+		Value::Bool(false) => out.push(FALSE),
+		Value::Bool(true) => out.push(TRUE),
+		#[cfg(feature = "bytes")]
+		Value::Byte(byte) => out.extend([BYTE, *byte]),
+		#[cfg(feature = "bytes")]
+		Value::Bytes(bytes) => {
+			out.push(BYTES);
+			write_bytes(bytes, out);
+		}
+		#[cfg(feature = "containers")]
+		Value::List(list) => {
+			out.push(LIST);
+			write_varint(list.len() as u64, out);
+
+			for value in list {
+				write_value(value, out);
+			}
+		}
+		#[cfg(feature = "containers")]
+		Value::Map(map) => {
+			out.push(MAP);
+			write_map(map, out);
+		}
+	}
+}
+
+struct Decoder<'a> {
+	input: &'a [u8],
+	index: usize,
+}
+
+impl Decoder<'_> {
+	fn error(&self, kind: ErrorKind, message: impl Into<String>) -> Error {
+		Error::new(kind, message.into(), self.index)
+	}
+
+	/// The error for a value whose cargo feature isn't enabled.
+	#[cfg(not(all(feature = "containers", feature = "bytes")))]
+	fn disabled_feature(&self, feature: &str) -> Error {
+		self.error(
+			ErrorKind::WrongValue,
+			format!("This value needs the `{feature}` feature of yadil"),
+		)
+	}
+
+	fn take(&mut self, len: usize) -> Result<&[u8]> {
+		if self.input.len() - self.index < len {
+			return Err(self.error(ErrorKind::UnexpectedEof, "Binary input is truncated"));
+		}
+
+		self.index += len;
+		Ok(&self.input[self.index - len..self.index])
+	}
+
+	fn varint(&mut self) -> Result<u64> {
+		let mut value = 0;
+
+		for shift in (0..64).step_by(7) {
+			let byte = self.take(1)?[0];
+			value |= ((byte & 0x7f) as u64) << shift;
+
+			if byte & 0x80 == 0 {
+				return Ok(value);
+			}
+		}
+
+		Err(self.error(ErrorKind::WrongValue, "Varint is too long"))
+	}
+
+	fn len(&mut self) -> Result<usize> {
+		let len = self.varint()?;
+
+		// Every element takes at least one byte, so this also keeps allocations in check.
+		if len > (self.input.len() - self.index) as u64 {
+			return Err(self.error(ErrorKind::UnexpectedEof, "Binary input is truncated"));
+		}
+
+		Ok(len as usize)
+	}
+
+	fn bytes(&mut self) -> Result<Vec<u8>> {
+		let len = self.len()?;
+		Ok(self.take(len)?.to_vec())
+	}
+
+	fn map(&mut self, depth: usize) -> Result<Map> {
+		let len = self.len()?;
+		let mut map = Map::with_capacity(len);
+
+		for _ in 0..len {
+			let key = self.bytes()?;
+			map.insert(key, self.value(depth)?);
+		}
+
+		Ok(map)
+	}
+
+	fn value(&mut self, depth: usize) -> Result<Value> {
+		if depth >= MAX_DEPTH {
+			return Err(self.error(ErrorKind::WrongValue, "Values are nested too deeply"));
+		}
+
+		Ok(match self.take(1)?[0] {
+			STRING => {
+				let bytes = self.bytes()?;
+				Value::String(
+					String::from_utf8(bytes)
+						.map_err(|_| self.error(ErrorKind::WrongValue, "Invalid utf8"))?,
+				)
+			}
+			UNSIGNED => Value::Unsigned(self.varint()? as usize),
+			SIGNED => {
+				let zigzag = self.varint()?;
+				Value::Signed(((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64)) as isize)
+			}
+			FLOAT => Value::Float(f64::from_le_bytes(self.take(8)?.try_into().unwrap())),
+			FALSE => Value::Bool(false),
+			TRUE => Value::Bool(true),
+			#[cfg(feature = "bytes")]
+			BYTE => Value::Byte(self.take(1)?[0]),
+			#[cfg(feature = "bytes")]
+			BYTES => Value::Bytes(self.bytes()?),
+			#[cfg(feature = "containers")]
+			LIST => {
+				let len = self.len()?;
+				let mut list = Vec::with_capacity(len);
+
+				for _ in 0..len {
+					list.push(self.value(depth + 1)?);
+				}
+
+				Value::List(list)
+			}
+			#[cfg(feature = "containers")]
+			MAP => Value::Map(self.map(depth + 1)?),
+			#[cfg(not(feature = "bytes"))]
+			BYTE | BYTES => return Err(self.disabled_feature("bytes")),
+			#[cfg(not(feature = "containers"))]
+			LIST | MAP => return Err(self.disabled_feature("containers")),
+			tag => {
+				return Err(self.error(ErrorKind::WrongValue, format!("Unknown binary tag `{tag}`")))
+			}
+		})
+	}
+}
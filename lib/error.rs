@@ -1,5 +1,7 @@
 //! Error type for the library.
 
+use std::ops::Range;
+
 /// A result type, containing either a value or an error.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -12,6 +14,12 @@ pub struct Error {
 	pub message: String,
 	/// The index of the error in the input string.
 	pub index: usize,
+	/// How many bytes from `index` the error covers, or 0 if it's only a position.
+	pub len: usize,
+	/// The line of `index`, starting at 1, or 0 if the error isn't about a text input.
+	pub line: usize,
+	/// The column of `index`, starting at 1, or 0 if the error isn't about a text input.
+	pub col: usize,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -19,6 +27,12 @@ pub enum ErrorKind {
 	UnexpectedChar,
 	EmptyIdent,
 	WrongValue,
+	UnexpectedEof,
+	Io,
+	DuplicateKey,
+	IntegerOverflow,
+	StepLimitExceeded,
+	DepthLimitExceeded,
 }
 
 impl Error {
@@ -28,6 +42,50 @@ impl Error {
 			kind,
 			message,
 			index,
+			len: 0,
+			line: 0,
+			col: 0,
+		}
+	}
+
+	/// Sets the line and column of the error from the input it was found in.
+	pub fn with_line_col(mut self, input: &[u8]) -> Error {
+		(self.line, self.col) = crate::index_to_line_col(input, self.index);
+		self
+	}
+
+	/// Sets the bytes of the input the error covers.
+	pub fn with_span(mut self, span: Range<usize>) -> Error {
+		self.index = span.start;
+		self.len = span.len();
+		self
+	}
+
+	/// The bytes of the input the error covers. Empty if the error is only a position.
+	pub fn span(&self) -> Range<usize> {
+		self.index..self.index + self.len
+	}
+
+	/// Recovers a yadil error from a type-erased one, such as a `Box<dyn std::error::Error>`.
+	pub fn from_boxed<'a>(error: &'a (dyn std::error::Error + 'static)) -> Option<&'a Error> {
+		error.downcast_ref()
+	}
+}
+
+/// Prints the kind, the position and the message, like `WrongValue at 3:5 (index 20): ...`.
+/// The line and column are left out when unset.
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:?} at ", self.kind)?;
+
+		if self.line != 0 {
+			write!(f, "{}:{} (index {})", self.line, self.col, self.index)?;
+		} else {
+			write!(f, "index {}", self.index)?;
 		}
+
+		write!(f, ": {}", self.message)
 	}
 }
+
+impl std::error::Error for Error {}
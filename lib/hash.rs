@@ -0,0 +1,170 @@
+//! Content hashing of messages.
+//!
+//! A message is hashed with SHA-256 over a canonical encoding of it: entries sorted by key at
+//! every level, and floats canonicalized with [`Value::canonicalize`]. Equal messages thus hash
+//! the same regardless of the order their entries were written or stored in.
+
+use crate::{Map, Message, Value};
+
+const STRING: u8 = 0;
+const UNSIGNED: u8 = 1;
+const SIGNED: u8 = 2;
+const FLOAT: u8 = 3;
+const BOOL: u8 = 4;
+#[cfg(feature = "bytes")]
+const BYTE: u8 = 5;
+#[cfg(feature = "bytes")]
+const BYTES: u8 = 6;
+#[cfg(feature = "containers")]
+const LIST: u8 = 7;
+#[cfg(feature = "containers")]
+const MAP: u8 = 8;
+
+impl Message {
+	/// Returns the SHA-256 hash of the message's canonical encoding, which is the same for
+	/// every message equal to this one, on any platform.
+	pub fn content_hash(&self) -> [u8; 32] {
+		let mut map = self.0.clone();
+		map.values_mut().for_each(Value::canonicalize);
+
+		let mut out = vec![];
+		write_entries(&map, &mut out);
+		sha256(&out)
+	}
+}
+
+fn write_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+	out.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+	out.extend_from_slice(bytes);
+}
+
+/// Writes the entries of a map, sorted by key.
+fn write_entries(map: &Map, out: &mut Vec<u8>) {
+	let mut entries: Vec<_> = map.iter().collect();
+	entries.sort_unstable_by_key(|(key, _)| *key);
+
+	for (key, value) in entries {
+		write_bytes(key, out);
+		write_value(value, out);
+	}
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) {
+	match value {
+		Value::String(string) => {
+			out.push(STRING);
+			write_bytes(string.as_bytes(), out);
+		}
+		Value::Unsigned(unsigned) => {
+			out.push(UNSIGNED);
+			out.extend_from_slice(&(*unsigned as u64).to_le_bytes());
+		}
+		Value::Signed(signed) => {
+			out.push(SIGNED);
+			out.extend_from_slice(&(*signed as i64).to_le_bytes());
+		}
+		Value::Float(float) => {
+			out.push(FLOAT);
+			out.extend_from_slice(&float.to_le_bytes());
+		}
+		Value::Bool(bool) => out.extend([BOOL, *bool as u8]),
+		#[cfg(feature = "bytes")]
+		Value::Byte(byte) => out.extend([BYTE, *byte]),
+		#[cfg(feature = "bytes")]
+		Value::Bytes(bytes) => {
+			out.push(BYTES);
+			write_bytes(bytes, out);
+		}
+		#[cfg(feature = "containers")]
+		Value::List(list) => {
+			out.push(LIST);
+			out.extend_from_slice(&(list.len() as u64).to_le_bytes());
+
+			for value in list {
+				write_value(value, out);
+			}
+		}
+		#[cfg(feature = "containers")]
+		Value::Map(map) => {
+			out.push(MAP);
+			out.extend_from_slice(&(map.len() as u64).to_le_bytes());
+			write_entries(map, out);
+		}
+	}
+}
+
+/// The round constants of SHA-256.
+const K: [u32; 64] = [
+	0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+	0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+	0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+	0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+	0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+	0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+	0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+	0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Hashes `input` with SHA-256.
+fn sha256(input: &[u8]) -> [u8; 32] {
+	let mut state: [u32; 8] = [
+		0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+		0x5be0cd19,
+	];
+
+	// Pad with a 1 bit, then zeros up to 8 bytes before a block boundary, then the bit length.
+	let mut message = input.to_vec();
+	message.push(0x80);
+
+	while message.len() % 64 != 56 {
+		message.push(0);
+	}
+
+	message.extend_from_slice(&((input.len() as u64) * 8).to_be_bytes());
+
+	for block in message.chunks_exact(64) {
+		let mut w = [0u32; 64];
+
+		for (i, word) in block.chunks_exact(4).enumerate() {
+			w[i] = u32::from_be_bytes(word.try_into().unwrap());
+		}
+
+		for i in 16..64 {
+			let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+			let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+			w[i] = w[i - 16]
+				.wrapping_add(s0)
+				.wrapping_add(w[i - 7])
+				.wrapping_add(s1);
+		}
+
+		let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+
+		for i in 0..64 {
+			let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+			let ch = (e & f) ^ (!e & g);
+			let t1 = h
+				.wrapping_add(s1)
+				.wrapping_add(ch)
+				.wrapping_add(K[i])
+				.wrapping_add(w[i]);
+			let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+			let maj = (a & b) ^ (a & c) ^ (b & c);
+			let t2 = s0.wrapping_add(maj);
+
+			(h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+		}
+
+		for (word, added) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+			*word = word.wrapping_add(added);
+		}
+	}
+
+	let mut out = [0; 32];
+
+	for (bytes, word) in out.chunks_exact_mut(4).zip(state) {
+		bytes.copy_from_slice(&word.to_be_bytes());
+	}
+
+	out
+}
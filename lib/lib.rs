@@ -1,21 +1,214 @@
 #![forbid(unsafe_code)]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "arena")]
+mod arena;
+mod binary;
 mod error;
+mod hash;
 mod parser;
+mod schema;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod serializer;
 
+use std::io::Read;
+
+#[cfg(feature = "arena")]
+pub use arena::{parse_in, ArenaMessage, ArenaValue};
+pub use binary::{from_binary, to_binary};
 pub use error::{Error, ErrorKind, Result};
 pub use parser::*;
+pub use schema::Schema;
+#[cfg(feature = "containers")]
+pub use serializer::ListWriter;
+pub use serializer::{to_bytes, to_string_aligned, to_string_pretty};
 
 /// Parse a YADIL message.
 pub fn parse(input: &[u8]) -> Result<parser::Message> {
 	parser::Parser::new(input).parse()
 }
 
+/// Parse a YADIL message, filling in the defaults of the optional keys it doesn't set.
+pub fn parse_with_schema(input: &[u8], schema: &Schema) -> Result<parser::Message> {
+	let mut message = parse(input)?;
+	schema.apply_defaults(&mut message);
+	Ok(message)
+}
+
+/// Read and parse a message framed by a 4-byte big-endian length prefix.
+///
+/// Fails with [`ErrorKind::UnexpectedEof`] if the stream ends inside the prefix (a message
+/// starting with "Truncated length prefix") or before the declared number of bytes (starting
+/// with "Truncated frame"), and with [`ErrorKind::Io`] if reading fails.
+///
+/// The frame is only allocated as its bytes arrive, so a prefix alone can't make this allocate
+/// the up to 4 GiB it declares.
+pub fn parse_length_prefixed<R: Read>(reader: &mut R) -> Result<parser::Message> {
+	let io_error = |err: std::io::Error| Error::new(ErrorKind::Io, err.to_string(), 0);
+
+	let mut prefix = vec![];
+	reader.take(4).read_to_end(&mut prefix).map_err(io_error)?;
+
+	let Ok(prefix) = <[u8; 4]>::try_from(prefix.as_slice()) else {
+		return Err(Error::new(
+			ErrorKind::UnexpectedEof,
+			format!(
+				"Truncated length prefix: the stream ended after {} of its 4 bytes",
+				prefix.len()
+			),
+			prefix.len(),
+		));
+	};
+
+	let len = u32::from_be_bytes(prefix) as usize;
+	let mut frame = vec![];
+	reader
+		.take(len as u64)
+		.read_to_end(&mut frame)
+		.map_err(io_error)?;
+
+	if frame.len() != len {
+		return Err(Error::new(
+			ErrorKind::UnexpectedEof,
+			format!(
+				"Truncated frame: its length is {len} bytes, but only {} were sent",
+				frame.len()
+			),
+			4 + frame.len(),
+		));
+	}
+
+	parse(&frame)
+}
+
+/// Parse a YADIL message, reading at most `max_steps` bytes of it.
+///
+/// This bounds the work done on untrusted input regardless of its shape, trailing whitespace and
+/// comments included. Fails with [`ErrorKind::StepLimitExceeded`] if the message doesn't end
+/// (with the input or a null byte) within the limit, including when the limit cuts an assignment
+/// or a trailing comment short.
+pub fn parse_with_step_limit(input: &[u8], max_steps: usize) -> Result<parser::Message> {
+	let (message, consumed, cut) = parse_within(input, max_steps)?;
+
+	if !cut {
+		return Ok(message);
+	}
+
+	// The trivia after the last assignment counts against the limit too, so a null byte must
+	// follow it within the limit.
+	let mut rest = parser::Parser::new(&input[..max_steps]);
+	rest.index = consumed;
+	rest.skip_trivia();
+
+	match input.get(rest.index) {
+		None | Some(0) => Ok(message),
+		Some(_) => Err(Error::new(
+			ErrorKind::StepLimitExceeded,
+			format!("Message is longer than the limit of {max_steps} steps"),
+			max_steps,
+		)),
+	}
+}
+
+/// Parse every message of a buffer holding null-terminated YADIL messages, in order.
+///
+/// Each frame is parsed on its own, and the first invalid one fails the whole buffer, with the
+/// error's index, line and column relative to the start of `input`. A final null byte is
+/// optional.
+pub fn parse_framed(input: &[u8]) -> Result<Vec<parser::Message>> {
+	frames(input).collect()
+}
+
+/// Parse a stream of null-terminated YADIL messages, yielding the result of each one in order.
+///
+/// Unlike [`parse_framed`], an invalid message doesn't end the stream: its error is yielded and
+/// parsing continues with the next message. Error indices, lines and columns are relative to the
+/// start of `input`, like with [`parse_framed`].
+pub fn parse_stream_lenient(input: &[u8]) -> impl Iterator<Item = Result<parser::Message>> + '_ {
+	frames(input)
+}
+
+/// Parses each null-terminated frame of `input` lazily, with error positions relative to the
+/// start of `input`. A final null byte is optional.
+fn frames(input: &[u8]) -> impl Iterator<Item = Result<parser::Message>> + '_ {
+	let frames = input.strip_suffix(&[0]).unwrap_or(input);
+	let mut offset = 0;
+
+	frames.split(|&byte| byte == 0).map(move |frame| {
+		let start = offset;
+		offset += frame.len() + 1;
+
+		parse(frame).map_err(|mut err| {
+			err.index += start;
+			err.with_line_col(input)
+		})
+	})
+}
+
+/// Parse the assignments of a YADIL message that end within the first `budget` bytes.
+///
+/// Only those bytes are read. Returns the parsed message and the number of bytes consumed, so
+/// parsing can be resumed by calling this again on `&input[consumed..]`. An assignment that
+/// crosses `budget` or isn't terminated yet is left unconsumed.
+pub fn parse_upto(input: &[u8], budget: usize) -> Result<(parser::Message, usize)> {
+	let (message, consumed, _) = parse_within(input, budget)?;
+	Ok((message, consumed))
+}
+
+/// Parses the assignments that end within the first `budget` bytes of `input`. Returns them,
+/// the number of bytes they take, and whether parsing stopped at the budget rather than at the
+/// end of the message.
+///
+/// Errors that cutting the input at the budget can cause, like running out of input, mean that
+/// an assignment crosses the budget.
+fn parse_within(input: &[u8], budget: usize) -> Result<(parser::Message, usize, bool)> {
+	let truncated = input.len() > budget;
+	let mut parser = parser::Parser::new(&input[..budget.min(input.len())]);
+	let mut body = Map::new();
+	let mut consumed = 0;
+
+	let cut = loop {
+		match parser.parse_one() {
+			Ok(Some(parser::Assign(key, value))) => {
+				body.insert(key, value);
+				consumed = parser.index;
+			}
+			// A null byte ends the message before the budget.
+			Ok(None) => break truncated && parser.at_end(),
+			Err(err)
+				if truncated
+					&& (err.kind == ErrorKind::UnexpectedEof || err.span().end >= budget) =>
+			{
+				break true
+			}
+			Err(err) => return Err(err),
+		}
+	};
+
+	Ok((parser::Message(body), consumed, cut))
+}
+
+/// Collects every comment of a YADIL message, along with its span.
+///
+/// The message is parsed to tell comments apart from `#` inside values, so this fails if the
+/// message is invalid.
+pub fn collect_comments(input: &[u8]) -> Result<Vec<parser::Comment>> {
+	let options = parser::ParserOptions {
+		capture_comments: true,
+		..Default::default()
+	};
+	let mut parser = parser::Parser::with_options(input, options);
+
+	while parser.parse_one()?.is_some() {}
+
+	Ok(parser.comments)
+}
+
 /// Converts an index to a line and column.
 pub fn index_to_line_col(input: &[u8], index: usize) -> (usize, usize) {
 	let mut line = 1;
-	let mut col = 2;
+	let mut col = 1;
 
 	for &byte in input.iter().take(index) {
 		if byte == b'\n' {
@@ -0,0 +1,120 @@
+//! Type aliases defined in the document, like `@alias port = uint;`
+
+use std::ops::Range;
+
+use super::{Parser, DISABLED_TYPES, SUPPORTED_TYPES};
+use crate::{ErrorKind, Result};
+
+impl Parser<'_> {
+	/// Skips whitespace, comments and alias definitions, leaving the index at the next
+	/// significant byte (or null byte).
+	pub(crate) fn skip_trivia_and_aliases(&mut self) -> Result<()> {
+		loop {
+			self.skip_trivia();
+
+			if !self.at_alias() {
+				return Ok(());
+			}
+
+			self.parse_alias()?;
+		}
+	}
+
+	/// Whether the index is at the `@alias` keyword, followed by whitespace.
+	fn at_alias(&self) -> bool {
+		let remaining = self.remaining();
+
+		remaining.starts_with(b"@alias")
+			&& remaining
+				.get(6)
+				.is_some_and(|byte| Self::IGNORE_BYTES.contains(byte))
+	}
+
+	/// Parses an alias definition, like `@alias port = uint;`. Later assignments can then use
+	/// `port` as their data type. Aliases can refer to other aliases, even ones defined later,
+	/// but not to themselves, and can't take the name of a built-in data type.
+	fn parse_alias(&mut self) -> Result<()> {
+		let start = self.index;
+		self.index += b"@alias".len();
+		let name = self.parse_ident()?;
+
+		if Self::is_built_in_type(&name) {
+			return Err(self.error_at(
+				start..self.index,
+				ErrorKind::UnexpectedChar,
+				format!(
+					"`{}` is a built-in data type, so it can't be an alias",
+					String::from_utf8_lossy(&name)
+				),
+			));
+		}
+
+		let mut data_type = vec![];
+
+		loop {
+			match self.next() {
+				Some(b';') => break,
+				Some(byte) if Self::IGNORE_BYTES.contains(&byte) => {}
+				Some(byte) => data_type.push(byte),
+				None => {
+					return Err(self.error(
+						ErrorKind::UnexpectedEof,
+						"Expected `;` after the alias before the end of the input",
+					))
+				}
+			}
+		}
+
+		if data_type.is_empty() {
+			return Err(self.error_at(
+				start..self.index,
+				ErrorKind::WrongValue,
+				"Expected the data type the alias stands for",
+			));
+		}
+
+		self.aliases.insert(name, data_type);
+		Ok(())
+	}
+
+	/// Whether `name` is a data type of the specification, whether or not its feature is on.
+	fn is_built_in_type(name: &[u8]) -> bool {
+		SUPPORTED_TYPES
+			.iter()
+			.chain(DISABLED_TYPES)
+			.any(|data_type| data_type.as_bytes() == name)
+			|| name.starts_with(b"l.")
+			|| name.starts_with(b"list.")
+	}
+
+	/// Whether `byte` can start a data type: a built-in one or an alias.
+	pub(crate) fn starts_data_type(&self, byte: &u8) -> bool {
+		Self::DATA_TYPE_START_BYTES.contains(byte)
+			|| self.aliases.keys().any(|alias| alias.first() == Some(byte))
+	}
+
+	/// The data type an alias stands for, following aliases of aliases, or `data_type` itself
+	/// if it isn't an alias. Fails if the aliases loop back, with the error covering `span`.
+	pub(crate) fn resolve_alias(&self, data_type: Vec<u8>, span: Range<usize>) -> Result<Vec<u8>> {
+		let Some(mut resolved) = self.aliases.get(&data_type) else {
+			return Ok(data_type);
+		};
+
+		// Without a loop, following every alias once reaches a data type that isn't one.
+		for _ in 0..self.aliases.len() {
+			match self.aliases.get(resolved) {
+				Some(target) => resolved = target,
+				None => return Ok(resolved.clone()),
+			}
+		}
+
+		Err(self.error_at(
+			span,
+			ErrorKind::UnexpectedChar,
+			format!(
+				"Alias `{}` refers back to itself",
+				String::from_utf8_lossy(&data_type)
+			),
+		))
+	}
+}
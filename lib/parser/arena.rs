@@ -0,0 +1,155 @@
+//! Parsing into a bump arena, without building the message on the heap first
+
+use std::collections::{hash_map::Entry, HashMap};
+
+use bumpalo::{collections::Vec as BumpVec, Bump};
+
+use super::{Assign, Parser, Value};
+use crate::{ArenaMessage, ArenaValue, ErrorKind, Result};
+
+/// The entries of a message or map being parsed into an arena, indexed by key so that repeated
+/// keys are found without walking the entries.
+struct ArenaEntries<'a> {
+	entries: BumpVec<'a, (&'a [u8], ArenaValue<'a>)>,
+	index: HashMap<&'a [u8], usize>,
+}
+
+impl<'a> ArenaEntries<'a> {
+	fn new_in(arena: &'a Bump) -> ArenaEntries<'a> {
+		ArenaEntries {
+			entries: BumpVec::new_in(arena),
+			index: HashMap::new(),
+		}
+	}
+
+	/// Adds an entry, replacing the value of `key` if it's already there. The key is only
+	/// copied into the arena the first time.
+	fn insert(&mut self, key: &[u8], value: ArenaValue<'a>, arena: &'a Bump) {
+		if let Some(&i) = self.index.get(key) {
+			self.entries[i].1 = value;
+			return;
+		}
+
+		let key = &*arena.alloc_slice_copy(key);
+
+		if let Entry::Vacant(entry) = self.index.entry(key) {
+			entry.insert(self.entries.len());
+			self.entries.push((key, value));
+		}
+	}
+}
+
+impl<'src> Parser<'src> {
+	/// Parses the message into `arena`. Lists and maps are built in the arena as they're read,
+	/// so only the assignment being read is ever on the heap. A key assigned more than once
+	/// keeps its last value.
+	///
+	/// Nested maps are kept on an explicit stack, like with [`Parser::parse_map`].
+	pub(crate) fn parse_in<'a>(&mut self, arena: &'a Bump) -> Result<ArenaMessage<'a>> {
+		let mut message = ArenaEntries::new_in(arena);
+		// Each open map, with its key.
+		#[cfg_attr(not(feature = "containers"), allow(unused_mut))]
+		let mut maps: Vec<(Vec<u8>, ArenaEntries<'a>)> = vec![];
+
+		loop {
+			// Aliases can only be defined at the top level, like with `Parser::parse`.
+			if maps.is_empty() {
+				self.skip_trivia_and_aliases()?;
+			} else {
+				self.skip_trivia();
+			}
+
+			let (key, value) = match self.input.get(self.index) {
+				None | Some(0) if !maps.is_empty() => {
+					return Err(self.error(
+						ErrorKind::UnexpectedEof,
+						"Expected `end;` to close the map before the end of the input",
+					))
+				}
+				None | Some(0) => break,
+				#[cfg(feature = "containers")]
+				Some(b'e') if !maps.is_empty() => {
+					self.parse_map_end()?;
+					let Some((key, map)) = maps.pop() else {
+						unreachable!("a map is open")
+					};
+					self.open_maps = maps.len();
+					(key, ArenaValue::Map(map.entries.into_bump_slice()))
+				}
+				Some(other) if self.starts_data_type(other) => {
+					let (data_type, span) = self.parse_data_type()?;
+
+					match &data_type[..] {
+						#[cfg(feature = "containers")]
+						b"m" | b"map" => {
+							let key = self.parse_ident_until(b':')?;
+							maps.push((key, ArenaEntries::new_in(arena)));
+							self.open_maps = maps.len();
+							self.check_depth(self.open_maps)?;
+							continue;
+						}
+						#[cfg(feature = "containers")]
+						ty if Self::is_list_type(ty) => self.parse_list_in(ty, arena)?,
+						ty => {
+							let Assign(key, value) = self.parse_typed_assign(ty, span)?;
+							(key, alloc_scalar(value, arena))
+						}
+					}
+				}
+				Some(&other) => return Err(self.expected_assign_start(other)),
+			};
+
+			match maps.last_mut() {
+				Some((_, map)) => map.insert(&key, value, arena),
+				None => message.insert(&key, value, arena),
+			}
+		}
+
+		Ok(ArenaMessage::new(
+			message.entries.into_bump_slice(),
+			message.index,
+		))
+	}
+
+	/// Parses a list assignment like [`Parser::parse_list_assign`], building its lists in
+	/// `arena`.
+	#[cfg(feature = "containers")]
+	fn parse_list_in<'a>(
+		&mut self,
+		data_type: &[u8],
+		arena: &'a Bump,
+	) -> Result<(Vec<u8>, ArenaValue<'a>)> {
+		let (levels, parse_element, spaced) = self.parse_list_type(data_type)?;
+		let ident = self.parse_ident()?;
+		let list = self.walk_list(
+			levels,
+			spaced,
+			|| BumpVec::new_in(arena),
+			|parser, list, bytes| {
+				list.push(alloc_scalar(parse_element(parser, bytes)?, arena));
+				Ok(())
+			},
+			|parent, list| parent.push(ArenaValue::List(list.into_bump_slice())),
+		)?;
+
+		self.parse_terminator("list")?;
+		Ok((ident, ArenaValue::List(list.into_bump_slice())))
+	}
+}
+
+/// Moves a value that isn't a list or map into `arena`.
+fn alloc_scalar(value: Value, arena: &Bump) -> ArenaValue<'_> {
+	match value {
+		Value::String(string) => ArenaValue::String(arena.alloc_str(&string)),
+		Value::Unsigned(unsigned) => ArenaValue::Unsigned(unsigned),
+		Value::Signed(signed) => ArenaValue::Signed(signed),
+		Value::Float(float) => ArenaValue::Float(float),
+		Value::Bool(bool) => ArenaValue::Bool(bool),
+		#[cfg(feature = "bytes")]
+		Value::Byte(byte) => ArenaValue::Byte(byte),
+		#[cfg(feature = "bytes")]
+		Value::Bytes(bytes) => ArenaValue::Bytes(arena.alloc_slice_copy(&bytes)),
+		#[cfg(feature = "containers")]
+		Value::List(_) | Value::Map(_) => unreachable!("containers are parsed into the arena"),
+	}
+}
@@ -0,0 +1,107 @@
+//! Parsing of assignments that borrow their strings from the input
+
+use std::borrow::Cow;
+
+use super::{Assign, BorrowedAssign, BorrowedValue, Parser, Value};
+use crate::Result;
+
+impl BorrowedAssign<'_> {
+	/// Copies whatever the assignment borrows from the input, so that it outlives it.
+	pub fn into_owned(self) -> Assign {
+		let BorrowedAssign(key, value) = self;
+		Assign(key.into_owned(), value.into_owned())
+	}
+}
+
+impl BorrowedValue<'_> {
+	/// Copies the string if it's borrowed from the input, so that the value outlives it. Lists
+	/// and maps are already owned, however deeply nested.
+	pub fn into_owned(self) -> Value {
+		match self {
+			BorrowedValue::String(string) => Value::String(string.into_owned()),
+			BorrowedValue::Other(value) => value,
+		}
+	}
+}
+
+impl<'src> Parser<'src> {
+	/// Parses the next top-level assignment like [`Parser::exprs`], but borrowing the key and
+	/// string value from the input rather than copying them, unless they have escapes or
+	/// whitespace to skip. Returns `None` at the end of the message.
+	pub fn next_borrowed(&mut self) -> Result<Option<BorrowedAssign<'src>>> {
+		self.skip_trivia_and_aliases()?;
+
+		match self.input.get(self.index) {
+			None | Some(0) => Ok(None),
+			Some(other) if self.starts_data_type(other) => {
+				let (data_type, span) = self.parse_data_type()?;
+
+				if let b"s" | b"str" = &data_type[..] {
+					let key = match self.borrow_key() {
+						Some(key) => Cow::Borrowed(key),
+						None => Cow::Owned(self.parse_ident()?),
+					};
+					let value = match self.borrow_string_value() {
+						Some(value) => Cow::Borrowed(value),
+						None => {
+							let value = self.parse_assign_value()?;
+							Cow::Owned(self.to_utf8(value)?)
+						}
+					};
+
+					return Ok(Some(BorrowedAssign(key, BorrowedValue::String(value))));
+				}
+
+				let Assign(key, value) = self.parse_typed_assign(&data_type, span)?;
+				Ok(Some(BorrowedAssign(
+					Cow::Owned(key),
+					BorrowedValue::Other(value),
+				)))
+			}
+			Some(&other) => Err(self.expected_assign_start(other)),
+		}
+	}
+
+	/// Borrows the key of an assignment after its `@`, leaving the index after its `=`, if it's
+	/// the exact bytes of the input. Otherwise, returns `None` without moving, so that the
+	/// copying parser can decode it (or report its error).
+	fn borrow_key(&mut self) -> Option<&'src [u8]> {
+		let input = self.input;
+		let key_len = input[self.index..].iter().position(|&byte| byte == b'=')?;
+		let key = &input[self.index..self.index + key_len];
+
+		if key.is_empty() || !key.iter().all(|byte| Self::is_plain(byte) && *byte != b';') {
+			return None;
+		}
+
+		if self.options.strict_idents && !key.iter().all(Self::is_strict_ident_byte) {
+			return None;
+		}
+
+		self.index += key_len + 1;
+		Some(key)
+	}
+
+	/// Borrows a string value after its `=`, leaving the index after its `;`, if it's the exact
+	/// bytes of the input. Otherwise, returns `None` without moving, like
+	/// [`Parser::borrow_key`].
+	fn borrow_string_value(&mut self) -> Option<&'src str> {
+		let input = self.input;
+		let value_len = input[self.index..].iter().position(|&byte| byte == b';')?;
+		let value = &input[self.index..self.index + value_len];
+
+		if value.is_empty() || value.contains(&b'\\') || !value.first().is_some_and(Self::is_plain)
+		{
+			return None;
+		}
+
+		let value = std::str::from_utf8(value).ok()?;
+		self.index += value_len + 1;
+		Some(value)
+	}
+
+	/// Whether `byte` is kept as written in keys, and at the start of values.
+	fn is_plain(byte: &u8) -> bool {
+		*byte != b'\\' && !Self::IGNORE_BYTES.contains(byte)
+	}
+}
@@ -1,22 +1,397 @@
 //! Parser function for complex types (list, map)
 
-use super::{Assign, Parser, Value};
-use crate::Result;
+use std::collections::HashSet;
 
-impl Parser<'_> {
+use super::{Assign, Map, Parser, Value};
+use crate::{ErrorKind, Result};
+
+/// Parses the bytes of a single list element.
+pub(crate) type ElementParser<'src> = fn(&mut Parser<'src>, Vec<u8>) -> Result<Value>;
+
+/// A map whose entries are being parsed.
+#[derive(Default)]
+struct OpenMap {
+	key: Vec<u8>,
+	/// Where the map's assignment starts.
+	start: usize,
+	entries: Map,
+	/// The keys whose repeated values were collected into a list.
+	collected: HashSet<Vec<u8>>,
+}
+
+impl OpenMap {
+	fn new(key: Vec<u8>, start: usize) -> OpenMap {
+		OpenMap {
+			key,
+			start,
+			entries: Map::new(),
+			collected: HashSet::new(),
+		}
+	}
+}
+
+impl<'src> Parser<'src> {
+	/// Parses a map assignment after its `@`, like `map @k: uint @a = 1; end;`. Its entries
+	/// are assignments of any type, closed by `end;` (or `e;`). Keys assigned more than once in
+	/// the same map are handled as [`ParserOptions::repeated_keys`] says.
+	///
+	/// Nested maps are kept on an explicit stack rather than parsed recursively, so nesting
+	/// can't overflow the call stack. Nesting past [`ParserOptions::max_depth`] is an error.
+	///
+	/// [`ParserOptions::repeated_keys`]: super::ParserOptions::repeated_keys
+	/// [`ParserOptions::max_depth`]: super::ParserOptions::max_depth
 	pub fn parse_map(&mut self) -> Result<Assign> {
-		todo!()
+		let key = self.parse_ident_until(b':')?;
+		let outer = self.open_maps;
+		// Each open map, with where its assignment starts, for errors about its key. The
+		// outermost one is left to whoever parses its assignment.
+		let mut stack = vec![OpenMap::new(key, self.index)];
+		self.open_maps = outer + stack.len();
+		self.check_depth(self.open_maps)?;
+
+		loop {
+			self.skip_trivia();
+			let start = self.index;
+
+			match self.input.get(self.index) {
+				None | Some(0) => {
+					return Err(self.error(
+						ErrorKind::UnexpectedEof,
+						"Expected `end;` to close the map before the end of the input",
+					))
+				}
+				Some(b'e') => {
+					self.parse_map_end()?;
+					let map = stack.pop().unwrap_or_default();
+					self.open_maps = outer + stack.len();
+					let assign = Assign(map.key, Value::Map(map.entries));
+
+					let Some(parent) = stack.last_mut() else {
+						return Ok(assign);
+					};
+
+					self.insert_assign(
+						&mut parent.entries,
+						&mut parent.collected,
+						assign,
+						map.start..self.index,
+					)?;
+				}
+				Some(other) if self.starts_data_type(other) => {
+					let (data_type, span) = self.parse_data_type()?;
+
+					if let b"m" | b"map" = &data_type[..] {
+						let key = self.parse_ident_until(b':')?;
+						stack.push(OpenMap::new(key, start));
+						self.open_maps = outer + stack.len();
+						self.check_depth(self.open_maps)?;
+						continue;
+					}
+
+					let assign = self.parse_typed_assign(&data_type, span)?;
+
+					if let Some(map) = stack.last_mut() {
+						self.insert_assign(
+							&mut map.entries,
+							&mut map.collected,
+							assign,
+							start..self.index,
+						)?;
+					}
+				}
+				Some(&other) => return Err(self.expected_assign_start(other)),
+			}
+		}
+	}
+
+	/// Fails with [`ErrorKind::DepthLimitExceeded`] if `depth` levels of lists and maps are
+	/// more than the options allow.
+	pub(crate) fn check_depth(&self, depth: usize) -> Result<()> {
+		if depth <= self.options.max_depth {
+			return Ok(());
+		}
+
+		Err(self.error(
+			ErrorKind::DepthLimitExceeded,
+			format!(
+				"Lists and maps are nested more than {} deep",
+				self.options.max_depth
+			),
+		))
+	}
+
+	/// Parses the `end;` (or `e;`) closing a map.
+	pub(crate) fn parse_map_end(&mut self) -> Result<()> {
+		let mut word = vec![];
+
+		loop {
+			match self.next() {
+				Some(b';') => break,
+				Some(byte) if Self::IGNORE_BYTES.contains(&byte) => {}
+				Some(byte) => word.push(byte),
+				None => {
+					self.missing_terminator()?;
+					break;
+				}
+			}
+		}
+
+		match &word[..] {
+			b"e" | b"end" => Ok(()),
+			other => Err(self.error(
+				ErrorKind::UnexpectedChar,
+				format!(
+					"Expected `end;` to close the map, got `{}`",
+					String::from_utf8_lossy(other)
+				),
+			)),
+		}
+	}
+
+	/// Whether `data_type` is a list type, like `list.uint` or `l.l.s`.
+	pub(crate) fn is_list_type(data_type: &[u8]) -> bool {
+		data_type.starts_with(b"l.") || data_type.starts_with(b"list.")
+	}
+
+	/// Parses a list assignment, like `list.uint @k = [1, 2, 3];`. `data_type` is the full
+	/// type, made of one `list` per level of nesting and then the type of the elements.
+	pub fn parse_list_assign(&mut self, data_type: &[u8]) -> Result<Assign> {
+		let (levels, parse_element, spaced) = self.parse_list_type(data_type)?;
+		let ident = self.parse_ident()?;
+		let list = self.parse_list(levels, parse_element, spaced)?;
+
+		self.parse_terminator("list")?;
+		Ok(Assign(ident, list))
 	}
 
-	pub fn parse_list(&mut self, _ty: Vec<u8>) -> Result<Value> {
-		todo!()
+	/// Splits a list type, like `list.uint` or `l.l.s`, into how many lists deep it is, the
+	/// parser of its elements, and whether whitespace can separate them.
+	pub(crate) fn parse_list_type(
+		&self,
+		data_type: &[u8],
+	) -> Result<(usize, ElementParser<'src>, bool)> {
+		let mut levels = 0;
+		let mut element: &[u8] = &[];
+
+		for (i, part) in data_type.split(|&byte| byte == b'.').enumerate() {
+			match part {
+				b"l" | b"list" if element.is_empty() => levels += 1,
+				_ if i == levels && !part.is_empty() => element = part,
+				_ => {
+					return Err(self.error(
+						ErrorKind::UnexpectedChar,
+						format!("Invalid list type `{}`", String::from_utf8_lossy(data_type)),
+					))
+				}
+			}
+		}
+
+		self.check_depth(self.open_maps + levels)?;
+
+		if element.is_empty() {
+			return Err(self.error(
+				ErrorKind::UnexpectedChar,
+				"Expected the type of the list elements, like `list.uint`",
+			));
+		}
+
+		let Some(parse_element) = Self::list_element_parser(element) else {
+			return Err(self.error(
+				ErrorKind::UnexpectedChar,
+				format!(
+					"Invalid list element type `{}`",
+					String::from_utf8_lossy(element)
+				),
+			));
+		};
+
+		// Strings and raw bytes can hold whitespace, so only commas separate them.
+		let spaced = !matches!(element, b"s" | b"str" | b"r" | b"raw");
+		Ok((levels, parse_element, spaced))
+	}
+
+	/// Parses a `[`-delimited list, `levels` lists deep, whose innermost elements are parsed by
+	/// `parse_element`. Elements are separated by commas, or by whitespace if `spaced` (nested
+	/// lists always can be). A trailing comma is allowed.
+	///
+	/// Nested lists are kept on an explicit stack rather than parsed recursively, so nesting
+	/// can't overflow the call stack.
+	pub fn parse_list(
+		&mut self,
+		levels: usize,
+		parse_element: ElementParser<'src>,
+		spaced: bool,
+	) -> Result<Value> {
+		self.walk_list(
+			levels,
+			spaced,
+			Vec::new,
+			|parser, list, bytes| {
+				list.push(parse_element(parser, bytes)?);
+				Ok(())
+			},
+			|parent, list| parent.push(Value::List(list)),
+		)
+		.map(Value::List)
+	}
+
+	/// Walks a list like [`Parser::parse_list`], starting each list with `new_list`, adding the
+	/// bytes of the innermost elements to it with `push_element`, and adding each nested list
+	/// to its parent with `push_list`.
+	pub(crate) fn walk_list<L>(
+		&mut self,
+		levels: usize,
+		spaced: bool,
+		mut new_list: impl FnMut() -> L,
+		mut push_element: impl FnMut(&mut Self, &mut L, Vec<u8>) -> Result<()>,
+		mut push_list: impl FnMut(&mut L, L),
+	) -> Result<L> {
+		self.skip_ignored();
+
+		if self.next() != Some(b'[') {
+			return Err(self.error(ErrorKind::UnexpectedChar, "Expected `[` to start a list"));
+		}
+
+		let mut stack = vec![new_list()];
+
+		loop {
+			self.skip_ignored();
+
+			match self.input.get(self.index) {
+				None => {
+					return Err(self.error(
+						ErrorKind::UnexpectedChar,
+						"Expected `]` to close the list before the end of the input",
+					))
+				}
+				Some(b';') => {
+					return Err(self.error(
+						ErrorKind::UnexpectedChar,
+						"Expected `]` to close the list before `;`",
+					))
+				}
+				Some(b']') => {
+					self.index += 1;
+					let Some(list) = stack.pop() else {
+						unreachable!("the outermost list is only popped once")
+					};
+
+					let Some(parent) = stack.last_mut() else {
+						return Ok(list);
+					};
+
+					push_list(parent, list);
+					self.parse_list_separator()?;
+				}
+				Some(b'[') if stack.len() < levels => {
+					self.index += 1;
+					stack.push(new_list());
+				}
+				Some(_) if stack.len() < levels => {
+					return Err(self.error(
+						ErrorKind::UnexpectedChar,
+						"Expected `[` to start a nested list",
+					))
+				}
+				Some(_) => {
+					let bytes = self.scan_list_element(spaced)?;
+
+					if let Some(list) = stack.last_mut() {
+						push_element(self, list, bytes)?;
+					}
+
+					self.parse_list_separator()?;
+				}
+			}
+		}
+	}
+
+	/// Reads the bytes of a list element up to its unescaped `,`, `]` or `;`, or whitespace if
+	/// `spaced`, which is left unconsumed. A backslash before one of [`Parser::LIST_ESCAPES`]
+	/// keeps that byte, `\n`, `\t`, `\r` and `\"` are decoded, and unescaped trailing whitespace
+	/// is trimmed.
+	fn scan_list_element(&mut self, spaced: bool) -> Result<Vec<u8>> {
+		let start = self.index;
+		let mut bytes = vec![];
+		let mut escaped_len = 0;
+		let mut escaped = false;
+
+		while let Some(&byte) = self.input.get(self.index) {
+			match byte {
+				b',' | b']' | b';' => break,
+				_ if spaced && Self::IGNORE_BYTES.contains(&byte) => break,
+				b'\\' => {
+					self.index += 1;
+					self.take_value_escape(&Self::LIST_ESCAPES, &mut bytes)?;
+					escaped_len = bytes.len();
+					escaped = true;
+					continue;
+				}
+				_ => bytes.push(byte),
+			}
+
+			self.index += 1;
+		}
+
+		while bytes.len() > escaped_len
+			&& bytes
+				.last()
+				.is_some_and(|byte| Self::IGNORE_BYTES.contains(byte))
+		{
+			bytes.pop();
+		}
+
+		self.value_span = (!escaped).then(|| start..start + bytes.len());
+		Ok(bytes)
+	}
+
+	/// Consumes the `,` after a list element, or leaves the index at the closing `]` (or a `;`,
+	/// which is an error) or at the next element if whitespace separates them.
+	fn parse_list_separator(&mut self) -> Result<()> {
+		let end = self.index;
+		self.skip_ignored();
+
+		match self.input.get(self.index) {
+			Some(b',') => {
+				self.index += 1;
+				Ok(())
+			}
+			Some(b']' | b';') | None => Ok(()),
+			Some(_) if self.index > end => Ok(()),
+			Some(&other) => Err(self.error(
+				ErrorKind::UnexpectedChar,
+				format!(
+					"Expected `,`, whitespace or `]` after a list element, got `{}`",
+					other as char
+				),
+			)),
+		}
 	}
 
-	pub fn parse_list_assign(&mut self) -> Result<Assign> {
-		todo!()
+	/// The parser of the elements of a list, if `data_type` can be one.
+	fn list_element_parser(data_type: &[u8]) -> Option<ElementParser<'src>> {
+		Some(match data_type {
+			b"s" | b"str" => Self::parse_string,
+			b"u" | b"uint" => Self::parse_unsigned,
+			b"i" | b"sint" => Self::parse_signed,
+			b"f" | b"float" => Self::parse_float,
+			b"b" | b"bool" => Self::parse_bool,
+			#[cfg(feature = "bytes")]
+			b"y" | b"byte" => Self::parse_byte,
+			#[cfg(feature = "bytes")]
+			b"r" | b"raw" => |_, bytes| Ok(Value::Bytes(bytes)),
+			_ => return None,
+		})
 	}
 
-	fn _parse_list_value(&mut self, _ty: Vec<u8>) -> Result<Value> {
-		todo!()
+	/// Skips whitespace, leaving the index at the next significant byte.
+	fn skip_ignored(&mut self) {
+		while self
+			.input
+			.get(self.index)
+			.is_some_and(|byte| Self::IGNORE_BYTES.contains(byte))
+		{
+			self.index += 1;
+		}
 	}
 }
@@ -0,0 +1,85 @@
+//! Event-based parsing, which walks a message without building it
+
+use super::{Assign, Event, Parser, Value};
+use crate::{ErrorKind, Result};
+
+impl Parser<'_> {
+	/// Parses the next event of the message, or returns `None` at its end (end of input or a
+	/// null byte).
+	///
+	/// Every assignment yields its key, then its value: a [`Event::Scalar`], a list between
+	/// [`Event::ListStart`] and [`Event::ListEnd`], or a map between [`Event::MapStart`] and
+	/// [`Event::MapEnd`]. Maps are streamed entry by entry, while each list is read whole
+	/// before its events are yielded. Repeated keys are yielded as they are, whatever
+	/// [`ParserOptions::repeated_keys`](super::ParserOptions::repeated_keys) says.
+	///
+	/// The events after an error are unspecified.
+	pub fn next_event(&mut self) -> Result<Option<Event>> {
+		if let Some(event) = self.pending_events.pop_front() {
+			return Ok(Some(event));
+		}
+
+		// Aliases can only be defined at the top level, like with `Parser::parse`.
+		if self.open_maps == 0 {
+			self.skip_trivia_and_aliases()?;
+		} else {
+			self.skip_trivia();
+		}
+
+		match self.input.get(self.index) {
+			None | Some(0) if self.open_maps > 0 => Err(self.error(
+				ErrorKind::UnexpectedEof,
+				"Expected `end;` to close the map before the end of the input",
+			)),
+			None | Some(0) => Ok(None),
+			#[cfg(feature = "containers")]
+			Some(b'e') if self.open_maps > 0 => {
+				self.parse_map_end()?;
+				self.open_maps -= 1;
+				Ok(Some(Event::MapEnd))
+			}
+			Some(other) if self.starts_data_type(other) => {
+				let (data_type, span) = self.parse_data_type()?;
+
+				#[cfg(feature = "containers")]
+				if let b"m" | b"map" = &data_type[..] {
+					let key = self.parse_ident_until(b':')?;
+					self.open_maps += 1;
+					self.check_depth(self.open_maps)?;
+					self.pending_events.push_back(Event::MapStart);
+					return Ok(Some(Event::KeyStart(key)));
+				}
+
+				let Assign(key, value) = self.parse_typed_assign(&data_type, span)?;
+				self.push_value_events(value);
+				Ok(Some(Event::KeyStart(key)))
+			}
+			Some(&other) => Err(self.expected_assign_start(other)),
+		}
+	}
+
+	/// Queues the events of a parsed value. Lists are walked with an explicit stack, like
+	/// they're parsed.
+	fn push_value_events(&mut self, value: Value) {
+		let mut stack = vec![vec![value].into_iter()];
+
+		while let Some(values) = stack.last_mut() {
+			match values.next() {
+				#[cfg(feature = "containers")]
+				Some(Value::List(list)) => {
+					self.pending_events.push_back(Event::ListStart);
+					stack.push(list.into_iter());
+				}
+				Some(scalar) => self.pending_events.push_back(Event::Scalar(scalar)),
+				None => {
+					stack.pop();
+
+					#[cfg(feature = "containers")]
+					if !stack.is_empty() {
+						self.pending_events.push_back(Event::ListEnd);
+					}
+				}
+			}
+		}
+	}
+}
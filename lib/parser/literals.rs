@@ -1,10 +1,11 @@
-//! Parser function for literal types (string, unsigned, signed, float, bool)
+//! Parser function for literal types (string, unsigned, signed, float, bool, byte)
 
 use super::{Assign, Parser, Value};
-use crate::{ErrorKind, Result};
+use crate::{Error, ErrorKind, Result};
 
 macro_rules! create_assign_parser {
-	($name:ident, $ty:ident, $parser:ident) => {
+	($(#[$attr:meta])* $name:ident, $ty:ident, $parser:ident) => {
+		$(#[$attr])*
 		#[inline]
 		#[doc(hidden)]
 		pub fn $name(&mut self) -> Result<Assign> {
@@ -15,8 +16,8 @@ macro_rules! create_assign_parser {
 }
 
 macro_rules! create_assign_parsers {
-	($($name:ident, $ty:ident, $parser:ident);* $(;)?) => {
-		$(create_assign_parser!($name, $ty, $parser);)*
+	($($(#[$attr:meta])* $name:ident, $ty:ident, $parser:ident);* $(;)?) => {
+		$(create_assign_parser!($(#[$attr])* $name, $ty, $parser);)*
 	};
 }
 
@@ -26,155 +27,378 @@ impl Parser<'_> {
 		Ok(Value::String(self.to_utf8(bytes)?))
 	}
 
+	/// Parses an unsigned integer, in decimal (`255`), hexadecimal (`0xff`), octal (`0o377`) or
+	/// binary (`0b11111111`).
 	pub fn parse_unsigned(&mut self, bytes: Vec<u8>) -> Result<Value> {
-		let mut total: usize = 0;
-
-		for byte in bytes.iter() {
-			if !(Self::ASCII_ZERO..=Self::ASCII_NINE).contains(byte) {
-				return Err(self.error(
-					ErrorKind::WrongValue,
-					format!("Invalid unsigned value `{}`", *byte as char),
-				));
-			}
-
-			total += (byte - Self::ASCII_ZERO) as usize;
+		let number = self.scan_number(&bytes, "unsigned")?;
+
+		if number.negative {
+			return Err(self.error(
+				ErrorKind::WrongValue,
+				"negative value not allowed for unsigned; use `i`/`sint`",
+			));
+		} else if number.fraction.is_some() {
+			return Err(self.error(ErrorKind::WrongValue, "Invalid unsigned value `.`"));
+		} else if number.exponent.is_some() {
+			return Err(self.error(ErrorKind::WrongValue, "Invalid unsigned value `e`"));
 		}
 
-		Ok(Value::Unsigned(total))
+		number
+			.integer_value()
+			.map(Value::Unsigned)
+			.ok_or_else(|| self.overflow("unsigned"))
 	}
 
 	pub fn parse_signed(&mut self, bytes: Vec<u8>) -> Result<Value> {
-		let mut total: isize = 0;
-		let mut is_negative = false;
-		let mut in_number = false;
-
-		for &byte in bytes.iter() {
-			if byte == b'-' {
-				if in_number {
-					return Err(self.error(
-						ErrorKind::WrongValue,
-						"Found `-` after number rather than before",
-					));
-				}
+		let number = self.scan_decimal(&bytes, "signed")?;
 
-				is_negative = !is_negative;
-				in_number = true;
-				continue;
-			}
-
-			if !(Self::ASCII_ZERO..=Self::ASCII_NINE).contains(&byte) {
-				return Err(self.error(ErrorKind::WrongValue, "Invalid signed value"));
-			}
-
-			total += (byte - Self::ASCII_ZERO) as isize;
+		if number.fraction.is_some() {
+			return Err(self.error(ErrorKind::WrongValue, "Invalid signed value `.`"));
+		} else if number.exponent.is_some() {
+			return Err(self.error(ErrorKind::WrongValue, "Invalid signed value `e`"));
 		}
 
-		if is_negative {
-			total = -total;
+		let mut total: isize = 0;
+
+		// Negative values are accumulated below zero, so that `isize::MIN` fits.
+		for byte in number.integer.iter().filter(|&&byte| byte != b'_') {
+			let digit = (byte - Self::ASCII_ZERO) as isize;
+			total = total
+				.checked_mul(10)
+				.and_then(|total| {
+					if number.negative {
+						total.checked_sub(digit)
+					} else {
+						total.checked_add(digit)
+					}
+				})
+				.ok_or_else(|| self.overflow("signed"))?;
 		}
 
 		Ok(Value::Signed(total))
 	}
 
+	/// Parses a float, which is either a decimal number with an optional exponent (`1.5e3`) or
+	/// one of the `nan`, `inf` and `-inf` literals.
 	pub fn parse_float(&mut self, bytes: Vec<u8>) -> Result<Value> {
-		let mut total = 0.0;
-		let mut dec_count = 0;
-		let mut is_negative = false;
-		let mut in_number = false;
-		let mut in_dec = false;
-
-		for &byte in bytes.iter() {
-			if byte == b'-' {
-				if in_number {
-					return Err(self.error(
-						ErrorKind::WrongValue,
-						"Found `-` after number rather than before",
-					));
-				}
-
-				is_negative = !is_negative;
-				in_number = true;
-				continue;
-			} else if byte == b'.' {
-				if in_dec {
-					return Err(self.error(
-						ErrorKind::WrongValue,
-						"Found `.` after decimal rather than before",
-					));
-				}
-
-				in_dec = true;
-				continue;
-			}
-
-			if !(Self::ASCII_ZERO..=Self::ASCII_NINE).contains(&byte) {
-				return Err(self.error(ErrorKind::WrongValue, "Invalid float value"));
-			}
-
-			if in_dec {
-				dec_count += 1;
-				total += (byte - Self::ASCII_ZERO) as f64 / 10.0_f64.powi(dec_count);
-			} else {
-				total += (byte - Self::ASCII_ZERO) as f64;
-			}
+		match &bytes[..] {
+			b"nan" => return Ok(Value::Float(f64::NAN)),
+			b"inf" | b"+inf" => return Ok(Value::Float(f64::INFINITY)),
+			b"-inf" => return Ok(Value::Float(f64::NEG_INFINITY)),
+			_ => {}
 		}
 
-		if is_negative {
-			total = -total;
-		}
-
-		Ok(Value::Float(total))
+		self.scan_decimal(&bytes, "float")?;
+
+		// Without its digit separators, the scanner only lets through the decimal syntax that the
+		// standard library parses, which rounds correctly unlike adding up each digit.
+		let float: String = bytes
+			.iter()
+			.filter(|&&byte| byte != b'_')
+			.map(|&byte| byte as char)
+			.collect();
+
+		float
+			.parse()
+			.ok()
+			.map(Value::Float)
+			.ok_or_else(|| self.error(ErrorKind::WrongValue, "Invalid float value"))
 	}
 
-	#[inline]
 	pub fn parse_bool(&mut self, bytes: Vec<u8>) -> Result<Value> {
 		Ok(Value::Bool(match &bytes[..] {
 			b"true" | b"t" => true,
 			b"false" | b"f" => false,
+			_ if self.options.numeric_bools => self.parse_numeric_bool(&bytes)?,
 			_ => return Err(self.error(ErrorKind::WrongValue, "Invalid bool value")),
 		}))
 	}
 
-	pub fn parse_assign(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
-		let mut data = vec![];
+	/// Parses a single byte, in decimal (`65`), hexadecimal (`0x41`), octal (`0o101`) or binary
+	/// (`0b1000001`).
+	///
+	/// Bytes use the `y`/`byte` data type rather than `b`, which already stands for bool.
+	#[cfg(feature = "bytes")]
+	pub fn parse_byte(&mut self, bytes: Vec<u8>) -> Result<Value> {
+		self.scan_byte(&bytes).map(Value::Byte)
+	}
+
+	#[cfg(feature = "bytes")]
+	fn scan_byte(&self, bytes: &[u8]) -> Result<u8> {
+		let number = self.scan_number(bytes, "byte")?;
+
+		if number.negative || number.fraction.is_some() || number.exponent.is_some() {
+			return Err(self.error(ErrorKind::WrongValue, "Invalid byte value"));
+		}
+
+		number
+			.integer_value()
+			.and_then(|byte| u8::try_from(byte).ok())
+			.ok_or_else(|| self.error(ErrorKind::WrongValue, "Byte value is above 255"))
+	}
+
+	/// Parses `0` as `false` and `1` as `true`, allowing any number of leading zeros and any
+	/// zeroed fraction (`0.0`, `01.00`).
+	fn parse_numeric_bool(&self, bytes: &[u8]) -> Result<bool> {
+		let number = self.scan_decimal(bytes, "bool")?;
+		let fraction = number.fraction.unwrap_or_default();
+		let zeros = number.integer.iter().take_while(|&&b| b == b'0').count();
+		let fraction_is_zero = fraction.iter().all(|&b| b == b'0');
+
+		match &number.integer[zeros..] {
+			_ if number.exponent.is_some() => {
+				Err(self.error(ErrorKind::WrongValue, "Invalid numeric bool value"))
+			}
+			b"" if fraction_is_zero => Ok(false),
+			b"1" if fraction_is_zero && !number.negative => Ok(true),
+			_ => Err(self.error(ErrorKind::WrongValue, "Invalid numeric bool value")),
+		}
+	}
+
+	/// Parses an identifier up to its `=`, skipping whitespace.
+	///
+	/// Identifiers made only of whitespace are empty, and thus rejected with
+	/// [`ErrorKind::EmptyIdent`].
+	pub fn parse_ident(&mut self) -> Result<Vec<u8>> {
+		self.parse_ident_until(b'=')
+	}
+
+	/// Parses an identifier up to `end` (`=` for values, `:` for maps), skipping whitespace.
+	///
+	/// A backslash before one of [`Parser::IDENT_ESCAPES`] keeps that byte in the identifier.
+	pub(crate) fn parse_ident_until(&mut self, end: u8) -> Result<Vec<u8>> {
 		let mut ident = vec![];
-		let mut in_value = false;
 
 		while let Some(next) = self.next() {
-			if self.maybe_escaped(next, b'=') {
+			let start = self.index - 1;
+
+			if let Some(escaped) = self.take_escaped(next, &Self::IDENT_ESCAPES) {
+				self.check_ident_byte(escaped, start)?;
+				ident.push(escaped);
+			} else if next == end {
 				if ident.is_empty() {
 					return Err(self.error(ErrorKind::EmptyIdent, "Identifier is empty"));
 				}
 
-				in_value = true;
-				continue;
-			} else if self.maybe_escaped(next, b';') {
+				break;
+			} else if next == b';' {
 				if ident.is_empty() {
 					return Err(self.error(
 						ErrorKind::UnexpectedChar,
 						"Unexpected semicolon before expr start",
 					));
-				} else if !in_value || data.is_empty() {
-					return Err(self.error(ErrorKind::WrongValue, "Expected value in expr"));
 				}
 
-				break;
-			} else if Self::IGNORE_BYTES.contains(&next) && (!in_value || data.is_empty()) {
-				continue;
+				return Err(self.error(ErrorKind::WrongValue, "Expected value in expr"));
+			} else if !Self::IGNORE_BYTES.contains(&next) {
+				self.check_ident_byte(next, start)?;
+				ident.push(next);
 			}
+		}
+
+		Ok(ident)
+	}
+
+	/// Whether `byte` is allowed in identifiers with [`ParserOptions::strict_idents`].
+	///
+	/// [`ParserOptions::strict_idents`]: super::ParserOptions::strict_idents
+	pub(crate) fn is_strict_ident_byte(byte: &u8) -> bool {
+		byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-')
+	}
+
+	/// Fails with [`ErrorKind::UnexpectedChar`] if the options don't allow `byte`, written from
+	/// `start` up to the index, in an identifier.
+	fn check_ident_byte(&self, byte: u8, start: usize) -> Result<()> {
+		if !self.options.strict_idents || Self::is_strict_ident_byte(&byte) {
+			return Ok(());
+		}
+
+		Err(self.error_at(
+			start..self.index,
+			ErrorKind::UnexpectedChar,
+			format!(
+				"`{}` isn't allowed in identifiers, only ASCII letters and digits, `_` and `-` are",
+				byte.escape_ascii()
+			),
+		))
+	}
+
+	/// Parses an identifier and the value after it, up to the terminating `;`. Leading
+	/// whitespace of the value is skipped.
+	///
+	/// A backslash before one of [`Parser::VALUE_ESCAPES`] keeps that byte in the value, and
+	/// `\n`, `\t`, `\r`, `\"` and `\u{...}` are decoded.
+	pub fn parse_assign(&mut self) -> Result<(Vec<u8>, Vec<u8>)> {
+		let ident = self.parse_ident()?;
+		Ok((ident, self.parse_assign_value()?))
+	}
+
+	/// Parses the value of an assignment after its `=`, like [`Parser::parse_assign`].
+	pub(crate) fn parse_assign_value(&mut self) -> Result<Vec<u8>> {
+		let mut data = vec![];
+		let mut escaped = false;
+
+		let end = loop {
+			let Some(next) = self.next() else {
+				self.missing_terminator()?;
+
+				if data.is_empty() {
+					return Err(self.error(ErrorKind::WrongValue, "Expected value in expr"));
+				}
 
-			if in_value {
+				break self.index;
+			};
+
+			if next == b'\\' {
+				self.take_value_escape(&Self::VALUE_ESCAPES, &mut data)?;
+				escaped = true;
+			} else if next == b';' {
+				if data.is_empty() {
+					return Err(self.error(ErrorKind::WrongValue, "Expected value in expr"));
+				}
+
+				break self.index - 1;
+			} else if !Self::IGNORE_BYTES.contains(&next) || !data.is_empty() {
 				data.push(next);
-			} else {
-				ident.push(next);
+			}
+		};
+
+		self.value_span = (!escaped).then(|| end - data.len()..end);
+		Ok(data)
+	}
+
+	/// Parses a raw assignment, keeping every byte of the value verbatim up to the terminating
+	/// `;`. Only `\;` and `\\` are unescaped.
+	#[cfg(feature = "bytes")]
+	pub fn raw_assign(&mut self) -> Result<Assign> {
+		let ident = self.parse_ident()?;
+		let mut data = vec![];
+
+		loop {
+			match self.next() {
+				Some(b';') => break,
+				Some(b'\\') => match self.next() {
+					Some(escaped @ (b';' | b'\\')) => data.push(escaped),
+					Some(other) => data.extend([b'\\', other]),
+					None => data.push(b'\\'),
+				},
+				Some(other) => data.push(other),
+				None => {
+					self.missing_terminator()?;
+					break;
+				}
 			}
 		}
 
-		// I'm not quite sure where, but somewhere index is growing when it shouldn't.
-		// This fixes it.
-		self.index -= 1;
+		Ok(Assign(ident, Value::Bytes(data)))
+	}
 
-		Ok((ident, data))
+	/// Parses a byte list assignment, like `x@k=[1, 0x02, 255];`. Elements are written as
+	/// `byte` values and separated by commas, whitespace or comments.
+	#[cfg(feature = "bytes")]
+	pub fn byte_list_assign(&mut self) -> Result<Assign> {
+		let ident = self.parse_ident()?;
+		self.skip_trivia();
+
+		if self.next() != Some(b'[') {
+			return Err(self.error(
+				ErrorKind::UnexpectedChar,
+				"Expected `[` to start a byte list",
+			));
+		}
+
+		let mut bytes = vec![];
+
+		loop {
+			self.skip_trivia();
+			let start = self.index;
+
+			while self
+				.input
+				.get(self.index)
+				.is_some_and(u8::is_ascii_alphanumeric)
+			{
+				self.index += 1;
+			}
+
+			let element = &self.input[start..self.index];
+
+			if !element.is_empty() {
+				self.value_span = Some(start..self.index);
+				bytes.push(self.scan_byte(element)?);
+				self.skip_trivia();
+
+				// Whitespace and comments separate elements as well as a comma.
+				if self.index > start + element.len()
+					&& self
+						.input
+						.get(self.index)
+						.is_some_and(u8::is_ascii_alphanumeric)
+				{
+					continue;
+				}
+			}
+
+			match self.next() {
+				Some(b',') if !element.is_empty() => {}
+				Some(b']') => break,
+				Some(other) => {
+					return Err(self.error(
+						ErrorKind::UnexpectedChar,
+						format!(
+							"Expected a byte or `]` in byte list, got `{}`",
+							other as char
+						),
+					))
+				}
+				None => {
+					return Err(self.error(
+						ErrorKind::UnexpectedChar,
+						"Expected `]` to close the byte list before the end of the input",
+					))
+				}
+			}
+		}
+
+		self.parse_terminator("byte list")?;
+		Ok(Assign(ident, Value::Bytes(bytes)))
+	}
+
+	/// Parses the `;` ending an assignment after its value, skipping whitespace and comments
+	/// before it. `value` names the value in the error message.
+	#[cfg(any(feature = "bytes", feature = "containers"))]
+	pub(crate) fn parse_terminator(&mut self, value: &str) -> Result<()> {
+		self.skip_trivia();
+
+		match self.next() {
+			Some(b';') => Ok(()),
+			Some(other) => Err(self.error(
+				ErrorKind::UnexpectedChar,
+				format!("Expected `;` after the {value}, got `{}`", other as char),
+			)),
+			None => self.missing_terminator(),
+		}
+	}
+
+	/// The error for an integer that doesn't fit its data type, covering the whole value.
+	pub(crate) fn overflow(&self, kind: &str) -> Error {
+		self.value_error(
+			ErrorKind::IntegerOverflow,
+			format!("Value is out of range for {kind}"),
+		)
+	}
+
+	/// Called when the input ends before an assignment's `;`, which is only accepted with
+	/// [`ParserOptions::allow_missing_final_terminator`](super::ParserOptions).
+	pub(crate) fn missing_terminator(&self) -> Result<()> {
+		if self.options.allow_missing_final_terminator {
+			Ok(())
+		} else {
+			Err(self.error(
+				ErrorKind::UnexpectedEof,
+				"Expected `;` before the end of the input",
+			))
+		}
 	}
 
 	create_assign_parsers!(
@@ -183,5 +407,7 @@ impl Parser<'_> {
 		signed_assign, Signed, parse_signed;
 		float_assign, Float, parse_float;
 		bool_assign, Bool, parse_bool;
+		#[cfg(feature = "bytes")]
+		byte_assign, Byte, parse_byte;
 	);
 }
@@ -0,0 +1,253 @@
+//! Accessors and helpers for parsed messages
+
+#[cfg(feature = "containers")]
+use super::Entry;
+use super::{Map, Message, Parser, Shape, Value};
+use crate::Result;
+#[cfg(feature = "containers")]
+use crate::{Error, ErrorKind};
+
+impl Message {
+	/// Creates an empty message.
+	pub fn new() -> Message {
+		Message(Map::new())
+	}
+
+	/// Sets `key` to `value`, returning the value it replaced, if any.
+	pub fn insert(&mut self, key: impl Into<Vec<u8>>, value: impl Into<Value>) -> Option<Value> {
+		self.0.insert(key.into(), value.into())
+	}
+
+	/// Returns the top-level layout of the document. Every document is currently a map of
+	/// keyed assignments.
+	pub fn shape(&self) -> Shape {
+		Shape::Map
+	}
+
+	/// Returns the value of `key`.
+	pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&Value> {
+		self.0.get(key.as_ref())
+	}
+
+	/// Returns the value of a UTF-8 `key`, like [`Message::get`].
+	pub fn get_str(&self, key: &str) -> Option<&Value> {
+		self.get(key)
+	}
+
+	/// Returns a mutable reference to the value of `key`.
+	pub fn get_mut(&mut self, key: impl AsRef<[u8]>) -> Option<&mut Value> {
+		self.0.get_mut(key.as_ref())
+	}
+
+	/// Iterates over the entries, in the order of the underlying [`Map`].
+	pub fn iter(&self) -> impl Iterator<Item = (&[u8], &Value)> {
+		self.into_iter()
+	}
+
+	/// Iterates over the entries sorted by their raw key bytes, leaving the storage untouched.
+	pub fn sorted_iter(&self) -> impl Iterator<Item = (&Vec<u8>, &Value)> {
+		let mut entries: Vec<_> = self.0.iter().collect();
+		entries.sort_unstable_by_key(|(key, _)| *key);
+		entries.into_iter()
+	}
+
+	/// Returns the top-level keys only present in this message, and those only present in
+	/// `other`, both sorted.
+	pub fn difference_keys(&self, other: &Message) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+		let only_in = |a: &Message, b: &Message| {
+			let mut keys: Vec<_> =
+				a.0.keys()
+					.filter(|key| !b.0.contains_key(*key))
+					.cloned()
+					.collect();
+			keys.sort_unstable();
+			keys
+		};
+
+		(only_in(self, other), only_in(other, self))
+	}
+
+	/// Returns the value of `key`, ignoring leading and trailing ASCII whitespace on both the
+	/// given key and the stored keys.
+	pub fn get_trimmed(&self, key: impl AsRef<[u8]>) -> Option<&Value> {
+		let key = key.as_ref().trim_ascii();
+
+		self.0
+			.iter()
+			.find(|(stored, _)| stored.trim_ascii() == key)
+			.map(|(_, value)| value)
+	}
+
+	/// Appends `value` to the list stored at `key`, creating a single-element list if `key` is
+	/// missing.
+	///
+	/// Fails with [`ErrorKind::WrongValue`] if `key` holds something other than a list.
+	#[cfg(feature = "containers")]
+	pub fn push_to_list(&mut self, key: impl Into<Vec<u8>>, value: Value) -> Result<()> {
+		match self.0.entry(key.into()) {
+			Entry::Vacant(entry) => {
+				entry.insert(Value::List(vec![value]));
+				Ok(())
+			}
+			Entry::Occupied(mut entry) => match entry.get_mut() {
+				Value::List(list) => {
+					list.push(value);
+					Ok(())
+				}
+				_ => Err(Error::new(
+					ErrorKind::WrongValue,
+					format!("`{}` is not a list", String::from_utf8_lossy(entry.key())),
+					0,
+				)),
+			},
+		}
+	}
+
+	/// Returns the value at a dotted `path` (e.g. `server.port`), descending through maps.
+	#[cfg(feature = "containers")]
+	pub fn get_path(&self, path: &str) -> Option<&Value> {
+		let mut segments = path.split('.');
+		let mut value = self.0.get(segments.next()?.as_bytes())?;
+
+		for segment in segments {
+			value = match value {
+				Value::Map(map) => map.get(segment.as_bytes())?,
+				_ => return None,
+			};
+		}
+
+		Some(value)
+	}
+
+	/// Sets the value at a dotted `path` (e.g. `server.port`), creating the intermediate maps
+	/// that don't exist yet and replacing the leaf.
+	///
+	/// Fails with [`ErrorKind::WrongValue`] if an intermediate key holds something other than a
+	/// map.
+	#[cfg(feature = "containers")]
+	pub fn set_path(&mut self, path: &str, value: Value) -> Result<()> {
+		let (parents, leaf) = match path.rsplit_once('.') {
+			Some((parents, leaf)) => (Some(parents), leaf),
+			None => (None, path),
+		};
+
+		let mut map = &mut self.0;
+
+		for segment in parents.into_iter().flat_map(|parents| parents.split('.')) {
+			let entry = map
+				.entry(segment.as_bytes().to_vec())
+				.or_insert_with(|| Value::Map(Map::new()));
+
+			map = match entry {
+				Value::Map(inner) => inner,
+				_ => {
+					return Err(Error::new(
+						ErrorKind::WrongValue,
+						format!("`{segment}` in `{path}` is not a map"),
+						0,
+					))
+				}
+			};
+		}
+
+		map.insert(leaf.as_bytes().to_vec(), value);
+		Ok(())
+	}
+
+	/// Checks that every key, including the keys of nested maps, is valid UTF-8.
+	///
+	/// Fails with the list of invalid keys otherwise.
+	pub fn validate_utf8_keys(&self) -> std::result::Result<(), Vec<Vec<u8>>> {
+		let mut invalid = vec![];
+		let mut maps = vec![&self.0];
+
+		while let Some(map) = maps.pop() {
+			for (key, value) in map {
+				if std::str::from_utf8(key).is_err() {
+					invalid.push(key.clone());
+				}
+
+				#[cfg(feature = "containers")]
+				{
+					let mut values = vec![value];
+
+					while let Some(value) = values.pop() {
+						match value {
+							Value::Map(inner) => maps.push(inner),
+							Value::List(list) => values.extend(list),
+							_ => {}
+						}
+					}
+				}
+
+				#[cfg(not(feature = "containers"))]
+				let _ = value;
+			}
+		}
+
+		if invalid.is_empty() {
+			Ok(())
+		} else {
+			Err(invalid)
+		}
+	}
+
+	/// Deep-merges `other` into this message: maps present on both sides are merged key by key,
+	/// and any other value from `other` replaces the existing one.
+	pub fn merge(&mut self, other: Message) {
+		merge_maps(&mut self.0, other.0);
+	}
+
+	/// Parses `input` and deep-merges it into this message, as [`Message::merge`] does.
+	///
+	/// Useful for layering configuration files over some defaults.
+	pub fn merge_from_bytes(&mut self, input: &[u8]) -> Result<()> {
+		let other = Parser::new(input).parse()?;
+		self.merge(other);
+		Ok(())
+	}
+}
+
+impl<K: Into<Vec<u8>>, V: Into<Value>> FromIterator<(K, V)> for Message {
+	fn from_iter<I: IntoIterator<Item = (K, V)>>(entries: I) -> Message {
+		Message(
+			entries
+				.into_iter()
+				.map(|(key, value)| (key.into(), value.into()))
+				.collect(),
+		)
+	}
+}
+
+impl IntoIterator for Message {
+	type Item = (Vec<u8>, Value);
+	type IntoIter = <Map as IntoIterator>::IntoIter;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.into_iter()
+	}
+}
+
+impl<'a> IntoIterator for &'a Message {
+	type Item = (&'a [u8], &'a Value);
+	type IntoIter = std::iter::Map<
+		<&'a Map as IntoIterator>::IntoIter,
+		fn((&'a Vec<u8>, &'a Value)) -> (&'a [u8], &'a Value),
+	>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.0.iter().map(|(key, value)| (key.as_slice(), value))
+	}
+}
+
+fn merge_maps(into: &mut Map, from: Map) {
+	for (key, value) in from {
+		match (into.get_mut(&key), value) {
+			#[cfg(feature = "containers")]
+			(Some(Value::Map(existing)), Value::Map(inner)) => merge_maps(existing, inner),
+			(_, value) => {
+				into.insert(key, value);
+			}
+		}
+	}
+}
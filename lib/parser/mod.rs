@@ -1,138 +1,858 @@
 //! Parser of the YADIL specification, in rust.
 
+mod alias;
+#[cfg(feature = "arena")]
+mod arena;
+mod borrowed;
+#[cfg(feature = "containers")]
 mod complex;
+mod event;
 mod literals;
+mod message;
+mod number;
+mod value;
 
-use std::collections::HashMap;
+#[cfg(not(feature = "ordered"))]
+use std::collections::{hash_map::Entry, HashMap};
+use std::{
+	borrow::Cow,
+	collections::{HashSet, VecDeque},
+	ops::Range,
+};
+
+#[cfg(feature = "ordered")]
+use indexmap::{map::Entry, IndexMap};
 
 use crate::{Error, ErrorKind, Result};
 
 /// Any valid value.
-#[derive(Debug, Clone)]
+///
+/// Floats are compared as `f64`s, so a value holding NaN isn't equal to itself, and `-0.0` is
+/// equal to `0.0`.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Value {
 	String(String),
 	Unsigned(usize),
 	Signed(isize),
 	Float(f64),
 	Bool(bool),
+	#[cfg(feature = "bytes")]
+	Byte(u8),
+	#[cfg(feature = "bytes")]
+	Bytes(Vec<u8>),
+	#[cfg(feature = "containers")]
 	List(Vec<Value>),
-	Map(HashMap<Vec<u8>, Value>),
+	/// Maps are keyed by raw bytes, like messages, rather than by values: keys never need to be
+	/// hashed as floats, so `Value` doesn't have to implement `Eq` or `Hash`.
+	#[cfg(feature = "containers")]
+	Map(Map),
+}
+
+/// The entries of a message or map value, keyed by raw bytes.
+///
+/// A `HashMap`, or with the `ordered` feature an `IndexMap` that keeps the entries in the order
+/// they were written.
+#[cfg(not(feature = "ordered"))]
+pub type Map = HashMap<Vec<u8>, Value>;
+
+/// The entries of a message or map value, keyed by raw bytes.
+///
+/// A `HashMap`, or with the `ordered` feature an `IndexMap` that keeps the entries in the order
+/// they were written.
+#[cfg(feature = "ordered")]
+pub type Map = IndexMap<Vec<u8>, Value>;
+
+/// A numeric value, whichever data type it was written with.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Number {
+	Unsigned(usize),
+	Signed(isize),
+	Float(f64),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TypedValue {
 	pub value: Value,
 	pub data_type: Vec<u8>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Assign(pub Vec<u8>, pub Value);
 
-#[derive(Debug, Clone)]
-pub struct Message(pub HashMap<Vec<u8>, Value>);
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Message(pub Map);
+
+/// An assignment whose key and string value borrow from the input when they have nothing to
+/// decode, yielded by [`Parser::next_borrowed`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorrowedAssign<'src>(pub Cow<'src, [u8]>, pub BorrowedValue<'src>);
+
+/// The value of a [`BorrowedAssign`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BorrowedValue<'src> {
+	/// A string value, borrowed unless it had escapes.
+	String(Cow<'src, str>),
+	/// Any other value, which is always owned.
+	Other(Value),
+}
+
+/// A step of a message, yielded by [`Parser::next_event`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+	/// The key of an assignment, followed by the events of its value.
+	KeyStart(Vec<u8>),
+	/// A value that isn't a list or map.
+	Scalar(Value),
+	#[cfg(feature = "containers")]
+	ListStart,
+	#[cfg(feature = "containers")]
+	ListEnd,
+	/// The start of a map, whose entries follow as keys and values until [`Event::MapEnd`].
+	#[cfg(feature = "containers")]
+	MapStart,
+	#[cfg(feature = "containers")]
+	MapEnd,
+}
+
+/// The top-level layout of a document.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Shape {
+	/// A set of keyed assignments.
+	Map,
+}
+
+/// Options changing how the parser treats its input. The defaults follow the specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParserOptions {
+	/// Accept `0` and `1` (including forms like `0.0`) as `false` and `true` in bool values.
+	pub numeric_bools: bool,
+	/// Record every comment skipped into [`Parser::comments`].
+	pub capture_comments: bool,
+	/// Record the span of every top-level assignment into [`Parser::spans`].
+	pub capture_spans: bool,
+	/// Accept a last assignment that reaches the end of the input without its `;`.
+	pub allow_missing_final_terminator: bool,
+	/// What to do with a key assigned more than once, at the top level or in the same map.
+	pub repeated_keys: RepeatedKeys,
+	/// Treat `##` as the start of a comment running to the end of the line, rather than as an
+	/// empty comment.
+	pub line_comments: bool,
+	/// Only accept ASCII letters and digits, `_` and `-` in identifiers, escaped or not.
+	pub strict_idents: bool,
+	/// Keep unknown escapes in values, like `\q`, as written rather than failing with
+	/// [`ErrorKind::WrongValue`].
+	pub lenient_escapes: bool,
+	/// How deep lists and maps can be nested, counting both, before parsing fails with
+	/// [`ErrorKind::DepthLimitExceeded`]. Defaults to 128.
+	pub max_depth: usize,
+}
+
+impl Default for ParserOptions {
+	fn default() -> Self {
+		ParserOptions {
+			numeric_bools: false,
+			capture_comments: false,
+			capture_spans: false,
+			allow_missing_final_terminator: false,
+			repeated_keys: RepeatedKeys::default(),
+			line_comments: false,
+			strict_idents: false,
+			lenient_escapes: false,
+			max_depth: 128,
+		}
+	}
+}
+
+impl ParserOptions {
+	/// Sets [`ParserOptions::numeric_bools`].
+	pub const fn numeric_bools(mut self, numeric_bools: bool) -> Self {
+		self.numeric_bools = numeric_bools;
+		self
+	}
+
+	/// Sets [`ParserOptions::capture_comments`].
+	pub const fn capture_comments(mut self, capture_comments: bool) -> Self {
+		self.capture_comments = capture_comments;
+		self
+	}
+
+	/// Sets [`ParserOptions::capture_spans`].
+	pub const fn capture_spans(mut self, capture_spans: bool) -> Self {
+		self.capture_spans = capture_spans;
+		self
+	}
+
+	/// Sets [`ParserOptions::allow_missing_final_terminator`].
+	pub const fn allow_missing_final_terminator(mut self, allow: bool) -> Self {
+		self.allow_missing_final_terminator = allow;
+		self
+	}
+
+	/// Sets [`ParserOptions::repeated_keys`].
+	pub const fn repeated_keys(mut self, repeated_keys: RepeatedKeys) -> Self {
+		self.repeated_keys = repeated_keys;
+		self
+	}
+
+	/// Sets [`ParserOptions::line_comments`].
+	pub const fn line_comments(mut self, line_comments: bool) -> Self {
+		self.line_comments = line_comments;
+		self
+	}
+
+	/// Sets [`ParserOptions::strict_idents`].
+	pub const fn strict_idents(mut self, strict_idents: bool) -> Self {
+		self.strict_idents = strict_idents;
+		self
+	}
+
+	/// Sets [`ParserOptions::lenient_escapes`].
+	pub const fn lenient_escapes(mut self, lenient_escapes: bool) -> Self {
+		self.lenient_escapes = lenient_escapes;
+		self
+	}
+
+	/// Sets [`ParserOptions::max_depth`].
+	pub const fn max_depth(mut self, max_depth: usize) -> Self {
+		self.max_depth = max_depth;
+		self
+	}
+}
+
+/// How the parser treats a key assigned more than once, at the top level or in the same map.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RepeatedKeys {
+	/// The last assignment replaces the previous ones.
+	#[default]
+	LastWins,
+	/// Fail with [`ErrorKind::DuplicateKey`], spanning the second assignment of the key.
+	Error,
+	/// Collect every value of the key into a list, in order.
+	#[cfg(feature = "containers")]
+	Collect,
+}
+
+/// A comment's span in the input (including both `#`), and its body.
+pub type Comment = (Range<usize>, Vec<u8>);
+
+/// A top-level assignment's key, and its span in the input, from the start of its data type to
+/// its `;` included.
+pub type AssignSpan = (Vec<u8>, Range<usize>);
+
+/// Every data type the parser accepts with the enabled features, in both its short and long
+/// form.
+pub const SUPPORTED_TYPES: &[&str] = &[
+	"s",
+	"str",
+	"u",
+	"uint",
+	"i",
+	"sint",
+	"f",
+	"float",
+	"b",
+	"bool",
+	#[cfg(feature = "bytes")]
+	"y",
+	#[cfg(feature = "bytes")]
+	"byte",
+	#[cfg(feature = "bytes")]
+	"x",
+	#[cfg(feature = "bytes")]
+	"bytes",
+	#[cfg(feature = "bytes")]
+	"r",
+	#[cfg(feature = "bytes")]
+	"raw",
+	#[cfg(feature = "containers")]
+	"l",
+	#[cfg(feature = "containers")]
+	"list",
+	#[cfg(feature = "containers")]
+	"m",
+	#[cfg(feature = "containers")]
+	"map",
+];
+
+/// The data types of the cargo features that aren't enabled. The parser still knows them by
+/// name, to reject them with an error naming their feature.
+const DISABLED_TYPES: &[&str] = &[
+	#[cfg(not(feature = "bytes"))]
+	"y",
+	#[cfg(not(feature = "bytes"))]
+	"byte",
+	#[cfg(not(feature = "bytes"))]
+	"x",
+	#[cfg(not(feature = "bytes"))]
+	"bytes",
+	#[cfg(not(feature = "bytes"))]
+	"r",
+	#[cfg(not(feature = "bytes"))]
+	"raw",
+	#[cfg(not(feature = "containers"))]
+	"l",
+	#[cfg(not(feature = "containers"))]
+	"list",
+	#[cfg(not(feature = "containers"))]
+	"m",
+	#[cfg(not(feature = "containers"))]
+	"map",
+];
 
 pub struct Parser<'a> {
 	pub input: &'a [u8],
 	pub index: usize,
+	pub options: ParserOptions,
+	/// The comments found so far, if [`ParserOptions::capture_comments`] is set.
+	pub comments: Vec<Comment>,
+	/// The top-level assignments parsed so far, in order, if [`ParserOptions::capture_spans`] is
+	/// set.
+	pub spans: Vec<AssignSpan>,
+	/// Where the bytes of the value being parsed are in the input, unless escapes changed them.
+	value_span: Option<Range<usize>>,
+	/// Events parsed but not yielded yet by [`Parser::next_event`].
+	pending_events: VecDeque<Event>,
+	/// How many maps the parser is inside of.
+	open_maps: usize,
+	/// The data type each alias defined so far stands for.
+	aliases: std::collections::HashMap<Vec<u8>, Vec<u8>>,
 }
 
 impl<'src> Parser<'src> {
 	/// The start bytes of a data type.
 	///
 	/// Contains the following: s (string or sint), u (unsigned), i (signed),
-	/// f (float), b (byte), l (list), m (map)
-	pub const DATA_TYPE_START_BYTES: [u8; 7] = [b's', b'u', b'i', b'f', b'b', b'l', b'm'];
+	/// f (float), b (bool), y (byte), x (byte list), r (raw), l (list), m (map)
+	pub const DATA_TYPE_START_BYTES: [u8; 10] =
+		[b's', b'u', b'i', b'f', b'b', b'y', b'x', b'r', b'l', b'm'];
 
 	/// Bytes to ignore.
 	pub const IGNORE_BYTES: [u8; 4] = [b' ', b'\n', b'\r', b'\t'];
 
+	/// Bytes that are kept in an identifier when preceded by a backslash.
+	pub const IDENT_ESCAPES: [u8; 8] = [b'=', b':', b';', b'\\', b' ', b'\n', b'\r', b'\t'];
+
+	/// Bytes that are kept in a value when preceded by a backslash. Values also take `\n`, `\t`,
+	/// `\r` and `\"`.
+	pub const VALUE_ESCAPES: [u8; 6] = [b';', b'\\', b' ', b'\n', b'\r', b'\t'];
+
+	/// Bytes that are kept in a list element when preceded by a backslash. Like values, list
+	/// elements also take `\n`, `\t`, `\r` and `\"`.
+	pub const LIST_ESCAPES: [u8; 8] = [b',', b']', b';', b'\\', b' ', b'\n', b'\r', b'\t'];
+
 	pub const ASCII_NINE: u8 = b'9';
 	pub const ASCII_ZERO: u8 = b'0';
 
-	pub fn new(input: &'src [u8]) -> Parser {
-		Parser { input, index: 0 }
+	pub fn new(input: &'src [u8]) -> Parser<'src> {
+		Parser::with_options(input, ParserOptions::default())
+	}
+
+	pub fn with_options(input: &'src [u8], options: ParserOptions) -> Parser<'src> {
+		Parser {
+			input,
+			index: 0,
+			options,
+			comments: vec![],
+			spans: vec![],
+			value_span: None,
+			pending_events: VecDeque::new(),
+			open_maps: 0,
+			aliases: Default::default(),
+		}
+	}
+
+	/// The part of the input that hasn't been consumed yet.
+	pub fn remaining(&self) -> &'src [u8] {
+		&self.input[self.index.min(self.input.len())..]
+	}
+
+	/// Whether the whole input has been consumed.
+	pub fn at_end(&self) -> bool {
+		self.index >= self.input.len()
 	}
 
 	pub fn parse(&mut self) -> Result<Message> {
-		let len = self.input.len();
+		let mut body = Map::new();
+		let mut collected = HashSet::new();
 
-		if self.input.is_empty() {
-			return Ok(Message(HashMap::new()));
+		// Empty and whitespace-only inputs are empty messages.
+		if self.input[self.index..]
+			.iter()
+			.all(|byte| Self::IGNORE_BYTES.contains(byte))
+		{
+			self.index = self.input.len();
+			return Ok(Message(body));
 		}
 
-		let mut body = HashMap::new();
+		loop {
+			self.skip_trivia_and_aliases()?;
+			let start = self.index;
+
+			let Some(assign) = self.parse_one()? else {
+				break;
+			};
+
+			self.insert_assign(&mut body, &mut collected, assign, start..self.index)?;
+		}
+
+		Ok(Message(body))
+	}
+
+	/// Adds an assignment spanning `span` to `map`, handling a key already in it as
+	/// [`ParserOptions::repeated_keys`] says. `collected` holds the keys of `map` whose values
+	/// were already collected into a list.
+	pub(crate) fn insert_assign(
+		&self,
+		map: &mut Map,
+		collected: &mut HashSet<Vec<u8>>,
+		Assign(key, value): Assign,
+		span: Range<usize>,
+	) -> Result<()> {
+		#[cfg(not(feature = "containers"))]
+		let _ = collected;
+
+		let mut entry = match map.entry(key) {
+			Entry::Vacant(entry) => {
+				entry.insert(value);
+				return Ok(());
+			}
+			Entry::Occupied(entry) => entry,
+		};
+
+		match self.options.repeated_keys {
+			RepeatedKeys::LastWins => {
+				entry.insert(value);
+			}
+			RepeatedKeys::Error => {
+				return Err(self.error_at(
+					span,
+					ErrorKind::DuplicateKey,
+					format!(
+						"`{}` is assigned more than once",
+						String::from_utf8_lossy(entry.key())
+					),
+				))
+			}
+			#[cfg(feature = "containers")]
+			RepeatedKeys::Collect => {
+				// The first repetition wraps the existing value, which may be a list itself.
+				if collected.insert(entry.key().clone()) {
+					let existing = entry.get_mut();
+					let first = std::mem::replace(existing, Value::List(vec![]));
+					*existing = Value::List(vec![first]);
+				}
+
+				if let Value::List(list) = entry.get_mut() {
+					list.push(value);
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Parses the top-level assignments lazily, one per iteration, so that parsing can stop
+	/// early.
+	///
+	/// Iteration ends at the end of the message, or right after yielding an error.
+	pub fn exprs(&mut self) -> Exprs<'_, 'src> {
+		Exprs {
+			parser: self,
+			failed: false,
+		}
+	}
+
+	/// Parses the next top-level assignment, skipping any whitespace and comments before it,
+	/// and leaves the index right after it so parsing can be interleaved with other work.
+	///
+	/// Returns `None` at the end of the message (end of input or a null byte).
+	pub fn parse_one(&mut self) -> Result<Option<Assign>> {
+		self.skip_trivia_and_aliases()?;
+		let start = self.index;
+
+		let assign = match self.input.get(self.index) {
+			None | Some(0) => return Ok(None), // End of message (null byte)
+			Some(other) if self.starts_data_type(other) => self.parse_assign_start()?,
+			Some(&other) => return Err(self.expected_assign_start(other)),
+		};
+
+		if self.options.capture_spans {
+			self.spans.push((assign.0.clone(), start..self.index));
+		}
+
+		Ok(Some(assign))
+	}
+
+	/// Returns the exact bytes of the input that the last top-level assignment of `key` was
+	/// parsed from, whitespace and comments inside it included, if
+	/// [`ParserOptions::capture_spans`] is set.
+	pub fn raw(&self, key: impl AsRef<[u8]>) -> Option<&'src [u8]> {
+		let key = key.as_ref();
+		let input = self.input;
+
+		self.spans
+			.iter()
+			.rev()
+			.find(|(spanned, _)| spanned == key)
+			.map(|(_, span)| &input[span.clone()])
+	}
+
+	/// Skips whitespace and comments, leaving the index at the next significant byte (or null
+	/// byte).
+	pub(crate) fn skip_trivia(&mut self) {
+		let len = self.input.len();
 
 		while self.index < len {
 			// Avoiding "Cannot borrow `self.input` as mutable more than once at a time"
 			let byte = self.input[self.index];
 			match byte {
-				0 => break,                        // End of message (null byte)
-				b' ' | b'\n' | b'\r' | b'\t' => {} // Initial whitespaces & newlines are ignored,
-				b'#' => {
-					// Comments
-					while self.index < len {
-						self.index += 1;
-
-						if self.maybe_escaped(self.input[self.index], b'#') {
-							break;
-						}
-					}
+				b' ' | b'\n' | b'\r' | b'\t' => {} // Whitespaces & newlines are ignored,
+				b'#' if self.index == 0 && self.input.get(1) == Some(&b'!') => {
+					self.skip_line_comment(1);
+					continue;
 				}
-				other if Self::DATA_TYPE_START_BYTES.contains(&other) => {
-					let Assign(key, value) = self.parse_assign_start()?;
-					body.insert(key, value);
+				// `##` is an empty comment by default. With `line_comments`, it instead comments
+				// out the rest of the line, `#` included, and `\#` isn't an escape there.
+				b'#' if self.options.line_comments
+					&& self.input.get(self.index + 1) == Some(&b'#') =>
+				{
+					self.skip_line_comment(2);
+					continue;
 				}
-				other => {
-					return Err(self.error(
-						ErrorKind::UnexpectedChar,
-						format!("Expected expression, got `{}`", other as char),
-					))
+				b'#' => {
+					self.skip_comment();
+					continue;
 				}
+				_ => break,
 			}
 
 			self.index += 1;
 		}
+	}
 
-		Ok(Message(body))
+	/// The error for a byte that can't start an assignment.
+	fn expected_assign_start(&self, byte: u8) -> Error {
+		match byte {
+			b'@' => self.error(ErrorKind::UnexpectedChar, "missing data type before `@`"),
+			other => self.error(
+				ErrorKind::UnexpectedChar,
+				format!("Expected expression, got `{}`", other as char),
+			),
+		}
+	}
+
+	/// Skips the comment starting at the current `#`, leaving the index after its closing `#`.
+	///
+	/// The comment is closed by the first `#` not preceded by a backslash, so `##` is an empty
+	/// comment and `\#` is a literal `#` in its body. An unterminated comment runs until the end
+	/// of the input.
+	fn skip_comment(&mut self) {
+		let len = self.input.len();
+		let start = self.index;
+		self.index += 1;
+
+		while self.index < len && !self.maybe_escaped(self.index, b'#') {
+			self.index += 1;
+		}
+
+		let end = (self.index + 1).min(len);
+
+		if self.options.capture_comments {
+			let mut body = vec![];
+
+			for &byte in &self.input[start + 1..self.index] {
+				if byte == b'#' && body.last() == Some(&b'\\') {
+					body.pop();
+				}
+
+				body.push(byte);
+			}
+
+			self.comments.push((start..end, body));
+		}
+
+		self.index = end;
+	}
+
+	/// Skips a comment running to the end of the line, like a `#!` line at the start of the
+	/// input or a `##` line comment, leaving the index at the end of the line. Its body starts
+	/// after the first `marker_len` bytes.
+	///
+	/// The line is recorded as a comment if comments are captured.
+	fn skip_line_comment(&mut self, marker_len: usize) {
+		let start = self.index;
+		let end = self.input[start..]
+			.iter()
+			.position(|&byte| byte == b'\n')
+			.map_or(self.input.len(), |len| start + len);
+
+		if self.options.capture_comments {
+			let body = self.input[start + marker_len..end].to_vec();
+			self.comments.push((start..end, body));
+		}
+
+		self.index = end;
 	}
 
 	fn parse_assign_start(&mut self) -> Result<Assign> {
+		let (data_type, span) = self.parse_data_type()?;
+		self.parse_typed_assign(&data_type, span)
+	}
+
+	/// Reads a data type up to its `@`, along with its span (excluding whitespace around it).
+	fn parse_data_type(&mut self) -> Result<(Vec<u8>, Range<usize>)> {
 		let mut data_type = vec![];
+		let mut span = self.index..self.index;
 
-		while let Some(next) = self.next() {
-			if self.maybe_escaped(next, b'@') {
+		loop {
+			let Some(next) = self.next() else {
+				return Err(self.error(
+					ErrorKind::UnexpectedEof,
+					"Expected `@` before the end of the input",
+				));
+			};
+
+			if self.maybe_escaped(self.index - 1, b'@') {
 				break;
 			} else if Self::IGNORE_BYTES.contains(&next) {
 				continue;
 			}
 
+			if data_type.is_empty() {
+				span.start = self.index - 1;
+			}
+
 			data_type.push(next);
+			span.end = self.index;
 		}
 
-		match &data_type[..] {
+		let data_type = self.resolve_alias(data_type, span.clone())?;
+		Ok((data_type, span))
+	}
+
+	/// Parses the rest of an assignment of the given data type, after its `@`.
+	fn parse_typed_assign(&mut self, data_type: &[u8], span: Range<usize>) -> Result<Assign> {
+		match data_type {
 			b"s" | b"str" => self.string_assign(),
 			b"u" | b"uint" => self.unsigned_assign(),
 			b"i" | b"sint" => self.signed_assign(),
 			b"f" | b"float" => self.float_assign(),
 			b"b" | b"bool" => self.bool_assign(),
-			b"l" | b"list" => todo!(), // self.parse_list_assign(),
-			b"m" | b"map" => todo!(),  // self.parse_map(),
-			_ => Err(self.error(ErrorKind::UnexpectedChar, "Invalid data type")),
+			#[cfg(feature = "bytes")]
+			b"y" | b"byte" => self.byte_assign(),
+			#[cfg(feature = "bytes")]
+			b"x" | b"bytes" => self.byte_list_assign(),
+			#[cfg(feature = "bytes")]
+			b"r" | b"raw" => self.raw_assign(),
+			#[cfg(not(feature = "bytes"))]
+			b"y" | b"byte" | b"x" | b"bytes" | b"r" | b"raw" => Err(self.disabled_feature("bytes")),
+			#[cfg(feature = "containers")]
+			ty if Self::is_list_type(ty) => self.parse_list_assign(ty),
+			#[cfg(feature = "containers")]
+			b"l" | b"list" => Err(self.error(
+				ErrorKind::UnexpectedChar,
+				"Expected the type of the list elements, like `list.uint`",
+			)),
+			#[cfg(feature = "containers")]
+			b"m" | b"map" => self.parse_map(),
+			#[cfg(not(feature = "containers"))]
+			b"l" | b"list" | b"m" | b"map" => Err(self.disabled_feature("containers")),
+			#[cfg(not(feature = "containers"))]
+			ty if ty.starts_with(b"l.") || ty.starts_with(b"list.") => {
+				Err(self.disabled_feature("containers"))
+			}
+			other => {
+				let mut message = format!("Invalid data type `{}`", String::from_utf8_lossy(other));
+
+				if let Some(suggestion) = Self::suggest_data_type(other) {
+					message.push_str(&format!(", did you mean `{suggestion}`?"));
+				}
+
+				Err(self.error_at(span, ErrorKind::UnexpectedChar, message))
+			}
 		}
 	}
 
+	/// Finds the long-form data type closest to a misspelled one, if any is close enough.
+	fn suggest_data_type(data_type: &[u8]) -> Option<&'static str> {
+		SUPPORTED_TYPES
+			.iter()
+			.filter(|name| name.len() > 1)
+			.map(|name| (edit_distance(data_type, name.as_bytes()), *name))
+			.filter(|&(distance, _)| distance <= 2 && distance < data_type.len())
+			.min_by_key(|&(distance, _)| distance)
+			.map(|(_, name)| name)
+	}
+
+	/// Builds an error at the current index, with its line and column.
 	fn error(&self, kind: ErrorKind, message: impl Into<String>) -> Error {
-		Error::new(kind, message.into(), self.index)
+		self.error_at(self.index..self.index, kind, message)
+	}
+
+	/// Builds an error covering `span`, with its line and column.
+	fn error_at(&self, span: Range<usize>, kind: ErrorKind, message: impl Into<String>) -> Error {
+		Error::new(kind, message.into(), 0)
+			.with_span(span)
+			.with_line_col(self.input)
+	}
+
+	/// The error for a data type whose cargo feature isn't enabled.
+	#[cfg(not(all(feature = "containers", feature = "bytes")))]
+	fn disabled_feature(&self, feature: &str) -> Error {
+		self.error(
+			ErrorKind::UnexpectedChar,
+			format!("This data type needs the `{feature}` feature of yadil"),
+		)
 	}
 
 	fn to_utf8(&self, input: Vec<u8>) -> Result<String> {
 		String::from_utf8(input).map_err(|_| self.error(ErrorKind::WrongValue, "Invalid utf8"))
 	}
 
-	/// Returns `true` if the current byte is the expected byte and the previous byte is not a
+	/// Returns `true` if the byte at `at` is the expected byte and the previous byte is not a
 	/// backslash (escape symbol).
-	fn maybe_escaped(&self, current: u8, expected: u8) -> bool {
-		current == expected && self.index > 0 && self.input[self.index - 1] != b'\\'
+	fn maybe_escaped(&self, at: usize, expected: u8) -> bool {
+		self.input[at] == expected && (at == 0 || self.input[at - 1] != b'\\')
+	}
+
+	/// If `current` (the byte just consumed) is a backslash followed by one of `escapes`,
+	/// consumes and returns that byte.
+	fn take_escaped(&mut self, current: u8, escapes: &[u8]) -> Option<u8> {
+		let escaped = *self.input.get(self.index)?;
+
+		if current != b'\\' || !escapes.contains(&escaped) {
+			return None;
+		}
+
+		self.index += 1;
+		Some(escaped)
+	}
+
+	/// Decodes the escape after a backslash (just consumed) in a value into `out`. A backslash
+	/// before one of `escapes` keeps that byte, `\n`, `\t`, `\r` and `\"` give a newline, tab,
+	/// carriage return and double quote, and `\u{1F600}` gives the UTF-8 bytes of a hex codepoint.
+	/// Any other escape is an error, or kept as written with
+	/// [`ParserOptions::lenient_escapes`].
+	fn take_value_escape(&mut self, escapes: &[u8], out: &mut Vec<u8>) -> Result<()> {
+		let start = self.index - 1;
+
+		let escaped = match self.input.get(self.index) {
+			Some(byte) if escapes.contains(byte) => *byte,
+			Some(b'n') => b'\n',
+			Some(b't') => b'\t',
+			Some(b'r') => b'\r',
+			Some(b'"') => b'"',
+			Some(b'u') => return self.take_unicode_escape(start, out),
+			Some(&other) if self.options.lenient_escapes => {
+				out.push(b'\\');
+				other
+			}
+			Some(&other) => {
+				return Err(self.error_at(
+					start..self.index + 1,
+					ErrorKind::WrongValue,
+					format!("Unknown escape `\\{}`", other.escape_ascii()),
+				))
+			}
+			None => {
+				return Err(self.error_at(
+					start..self.index,
+					ErrorKind::UnexpectedEof,
+					"Expected an escaped byte after `\\`",
+				))
+			}
+		};
+
+		self.index += 1;
+		out.push(escaped);
+		Ok(())
+	}
+
+	/// Decodes the `u{...}` of a unicode escape starting at `start`, with the index at the `u`.
+	fn take_unicode_escape(&mut self, start: usize, out: &mut Vec<u8>) -> Result<()> {
+		let rest = &self.input[self.index + 1..];
+
+		// The input ends inside the escape, like `\u{1F`.
+		if rest.len() <= 7
+			&& rest.first().is_none_or(|&byte| byte == b'{')
+			&& rest.iter().skip(1).all(u8::is_ascii_hexdigit)
+		{
+			return Err(self.error_at(
+				start..self.input.len(),
+				ErrorKind::UnexpectedEof,
+				"Expected `}` to close the unicode escape before the end of the input",
+			));
+		}
+
+		let digits = rest
+			.strip_prefix(b"{")
+			.and_then(|rest| Some(&rest[..rest.iter().position(|&byte| byte == b'}')?]))
+			.filter(|digits| (1..=6).contains(&digits.len()))
+			.filter(|digits| digits.iter().all(u8::is_ascii_hexdigit));
+
+		let Some(digits) = digits else {
+			return Err(self.error_at(
+				start..self.index + 1,
+				ErrorKind::WrongValue,
+				"Expected a hex codepoint of 1 to 6 digits, like `\\u{1F600}`",
+			));
+		};
+
+		// `{`, the digits and `}`.
+		self.index += digits.len() + 3;
+
+		let digits = std::str::from_utf8(digits).unwrap_or_default();
+		let codepoint = u32::from_str_radix(digits, 16).unwrap_or(u32::MAX);
+
+		let Some(char) = char::from_u32(codepoint) else {
+			return Err(self.error_at(
+				start..self.index,
+				ErrorKind::WrongValue,
+				format!("Invalid unicode codepoint `\\u{{{digits}}}`"),
+			));
+		};
+
+		out.extend_from_slice(char.encode_utf8(&mut [0; 4]).as_bytes());
+		Ok(())
+	}
+}
+
+/// Levenshtein distance between two byte strings.
+fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+	let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+	for (i, &byte_a) in a.iter().enumerate() {
+		let mut current = vec![i + 1];
+
+		for (j, &byte_b) in b.iter().enumerate() {
+			let substitution = previous[j] + usize::from(byte_a != byte_b);
+			current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+		}
+
+		previous = current;
+	}
+
+	previous[b.len()]
+}
+
+/// Iterator over the top-level assignments of a message, returned by [`Parser::exprs`].
+pub struct Exprs<'p, 'src> {
+	parser: &'p mut Parser<'src>,
+	failed: bool,
+}
+
+impl Iterator for Exprs<'_, '_> {
+	type Item = Result<Assign>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.failed {
+			return None;
+		}
+
+		let next = self.parser.parse_one().transpose();
+		self.failed = matches!(next, Some(Err(_)));
+		next
 	}
 }
 
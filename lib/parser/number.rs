@@ -0,0 +1,315 @@
+//! Scanner shared by the numeric parsers (unsigned, signed, float)
+
+use super::Parser;
+use crate::{Error, ErrorKind, Result};
+
+/// The components of a numeric literal, as written in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ScannedNumber<'a> {
+	/// Whether the literal started with a `-` sign.
+	pub negative: bool,
+	/// Digits before the decimal point.
+	pub integer: &'a [u8],
+	/// Digits after the decimal point, if there was one.
+	pub fraction: Option<&'a [u8]>,
+	/// Digits after the `e` or `E`, with their sign, if there was one.
+	pub exponent: Option<&'a [u8]>,
+	/// The radix of the digits: 16, 8 or 2 after a `0x`, `0o` or `0b` prefix, and 10 otherwise.
+	pub radix: u32,
+}
+
+impl ScannedNumber<'_> {
+	/// Adds up the digits before the decimal point in the number's radix, skipping separators,
+	/// or returns `None` if the total overflows.
+	pub fn integer_value(&self) -> Option<usize> {
+		self.integer
+			.iter()
+			.filter(|&&byte| byte != b'_')
+			.try_fold(0usize, |total, &byte| {
+				let digit = (byte as char).to_digit(self.radix)?;
+				total
+					.checked_mul(self.radix as usize)?
+					.checked_add(digit as usize)
+			})
+	}
+}
+
+impl Parser<'_> {
+	/// Splits a numeric literal into its components, validating every byte.
+	///
+	/// Either side of the decimal point may be empty (`.5`, `5.`), but not both. An exponent
+	/// (`1.5e3`, `2E-2`) needs at least one digit. Underscores can separate digits (`1_000`), and
+	/// are left in the components for the caller to skip.
+	///
+	/// A `0x` (hexadecimal), `0o` (octal) or `0b` (binary) prefix after the sign is followed by
+	/// digits of that radix in either case, without a fraction, exponent or separators.
+	///
+	/// `kind` names the data type being parsed, and is only used in error messages.
+	pub(crate) fn scan_number<'b>(&self, bytes: &'b [u8], kind: &str) -> Result<ScannedNumber<'b>> {
+		let (negative, rest) = match bytes.split_first() {
+			Some((b'-', rest)) => (true, rest),
+			Some((b'+', rest)) => (false, rest),
+			_ => (false, bytes),
+		};
+		let sign_len = bytes.len() - rest.len();
+
+		let radix = match rest {
+			[b'0', b'x', ..] => 16,
+			[b'0', b'o', ..] => 8,
+			[b'0', b'b', ..] => 2,
+			_ => 10,
+		};
+
+		if radix != 10 {
+			return self.scan_radix(negative, &rest[2..], radix, kind);
+		}
+
+		let (rest, exponent) = match rest.iter().position(|&byte| matches!(byte, b'e' | b'E')) {
+			Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+			None => (rest, None),
+		};
+
+		let mut number = ScannedNumber {
+			negative,
+			integer: rest,
+			fraction: None,
+			exponent,
+			radix,
+		};
+
+		for (i, &byte) in rest.iter().enumerate() {
+			let message = match byte {
+				Self::ASCII_ZERO..=Self::ASCII_NINE | b'_' => continue,
+				b'.' if number.fraction.is_none() => {
+					number.integer = &rest[..i];
+					number.fraction = Some(&rest[i + 1..]);
+					continue;
+				}
+				b'.' => "Found `.` after decimal rather than before".to_string(),
+				b'-' | b'+' => format!("Found `{}` after number rather than before", byte as char),
+				other => format!("Invalid {kind} value `{}`", other as char),
+			};
+
+			return Err(self.digit_error(bytes, sign_len + i, message));
+		}
+
+		if number.integer.is_empty() && number.fraction.unwrap_or_default().is_empty() {
+			return Err(self.value_error(
+				ErrorKind::WrongValue,
+				format!("Expected digits in {kind} value"),
+			));
+		}
+
+		if let Some(i) = misplaced_separator(rest) {
+			return Err(self.misplaced_separator(bytes, sign_len + i, kind));
+		}
+
+		if let Some(exponent) = exponent {
+			let digits = match exponent.split_first() {
+				Some((b'-' | b'+', digits)) => digits,
+				_ => exponent,
+			};
+			let message = format!("Expected digits in the exponent of {kind} value");
+
+			if digits.is_empty() {
+				// Points at the `e`.
+				return Err(self.digit_error(bytes, bytes.len() - exponent.len() - 1, message));
+			} else if let Some(i) = digits
+				.iter()
+				.position(|&byte| !byte.is_ascii_digit() && byte != b'_')
+			{
+				return Err(self.digit_error(digits, i, message));
+			} else if let Some(i) = misplaced_separator(digits) {
+				return Err(self.misplaced_separator(digits, i, kind));
+			}
+		}
+
+		Ok(number)
+	}
+
+	/// Scans the digits of a literal written in `radix`, after its prefix. A digit outside the
+	/// radix is an error pointing at it.
+	fn scan_radix<'b>(
+		&self,
+		negative: bool,
+		digits: &'b [u8],
+		radix: u32,
+		kind: &str,
+	) -> Result<ScannedNumber<'b>> {
+		if digits.is_empty() {
+			return Err(self.value_error(
+				ErrorKind::WrongValue,
+				format!("Expected digits after the radix prefix of {kind} value"),
+			));
+		}
+
+		if let Some(i) = digits
+			.iter()
+			.position(|&byte| !(byte as char).is_digit(radix))
+		{
+			return Err(self.digit_error(
+				digits,
+				i,
+				format!(
+					"Invalid base {radix} digit `{}` in {kind} value",
+					digits[i] as char
+				),
+			));
+		}
+
+		Ok(ScannedNumber {
+			negative,
+			integer: digits,
+			fraction: None,
+			exponent: None,
+			radix,
+		})
+	}
+
+	/// Scans a number like [`Parser::scan_number`], failing if it has a radix prefix, which only
+	/// unsigned values and bytes take. The error points at the prefix's letter.
+	pub(crate) fn scan_decimal<'b>(
+		&self,
+		bytes: &'b [u8],
+		kind: &str,
+	) -> Result<ScannedNumber<'b>> {
+		let number = self.scan_number(bytes, kind)?;
+
+		if number.radix != 10 {
+			let prefix = bytes.len() - number.integer.len() - 1;
+			return Err(self.digit_error(
+				bytes,
+				prefix,
+				format!("Invalid {kind} value `{}`", bytes[prefix] as char),
+			));
+		}
+
+		Ok(number)
+	}
+
+	/// The error for the byte at `i` of `digits`, which end the value being parsed. Points at
+	/// that byte if the value was read from the input unchanged.
+	fn digit_error(&self, digits: &[u8], i: usize, message: String) -> Error {
+		match &self.value_span {
+			Some(span) if span.len() >= digits.len() => {
+				let at = span.end - digits.len() + i;
+				self.error_at(at..at + 1, ErrorKind::WrongValue, message)
+			}
+			_ => self.error(ErrorKind::WrongValue, message),
+		}
+	}
+
+	/// The error for the value being parsed as a whole. Covers it if it was read from the input
+	/// unchanged.
+	pub(crate) fn value_error(&self, kind: ErrorKind, message: String) -> Error {
+		match &self.value_span {
+			Some(span) => self.error_at(span.clone(), kind, message),
+			None => self.error(kind, message),
+		}
+	}
+
+	/// The error for the `_` at `i` of `digits`, which end the value being parsed.
+	fn misplaced_separator(&self, digits: &[u8], i: usize, kind: &str) -> Error {
+		self.digit_error(
+			digits,
+			i,
+			format!("Misplaced `_` in {kind} value, it can only go between two digits"),
+		)
+	}
+}
+
+/// The index of the first `_` in `bytes` that isn't between two digits, if any.
+fn misplaced_separator(bytes: &[u8]) -> Option<usize> {
+	bytes.iter().enumerate().position(|(i, &byte)| {
+		byte == b'_'
+			&& !(i > 0
+				&& bytes[i - 1].is_ascii_digit()
+				&& bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn scan(bytes: &[u8]) -> Result<ScannedNumber<'_>> {
+		Parser::new(b"").scan_number(bytes, "test")
+	}
+
+	fn number<'a>(
+		negative: bool,
+		integer: &'a [u8],
+		fraction: Option<&'a [u8]>,
+		exponent: Option<&'a [u8]>,
+		radix: u32,
+	) -> ScannedNumber<'a> {
+		ScannedNumber {
+			negative,
+			integer,
+			fraction,
+			exponent,
+			radix,
+		}
+	}
+
+	#[test]
+	fn signs() {
+		assert_eq!(scan(b"12").unwrap(), number(false, b"12", None, None, 10));
+		assert_eq!(scan(b"-12").unwrap(), number(true, b"12", None, None, 10));
+		assert_eq!(scan(b"+12").unwrap(), number(false, b"12", None, None, 10));
+		assert!(scan(b"1-2").is_err());
+		assert!(scan(b"--1").is_err());
+		assert!(scan(b"-").is_err());
+	}
+
+	#[test]
+	fn fractions() {
+		assert_eq!(
+			scan(b"1_0.25").unwrap(),
+			number(false, b"1_0", Some(b"25"), None, 10)
+		);
+		assert_eq!(
+			scan(b".5").unwrap(),
+			number(false, b"", Some(b"5"), None, 10)
+		);
+		assert_eq!(
+			scan(b"5.").unwrap(),
+			number(false, b"5", Some(b""), None, 10)
+		);
+		assert!(scan(b".").is_err());
+		assert!(scan(b"1.2.3").is_err());
+	}
+
+	#[test]
+	fn exponents() {
+		assert_eq!(
+			scan(b"-1.5e3").unwrap(),
+			number(true, b"1", Some(b"5"), Some(b"3"), 10)
+		);
+		assert_eq!(
+			scan(b"2E-2").unwrap(),
+			number(false, b"2", None, Some(b"-2"), 10)
+		);
+		assert!(scan(b"1e").is_err());
+		assert!(scan(b"1e+").is_err());
+		assert!(scan(b"1e_2").is_err());
+	}
+
+	#[test]
+	fn radix_prefixes() {
+		let hex = scan(b"0xfF").unwrap();
+		assert_eq!(hex, number(false, b"fF", None, None, 16));
+		assert_eq!(hex.integer_value(), Some(255));
+		assert_eq!(scan(b"0o17").unwrap().integer_value(), Some(15));
+		assert_eq!(
+			scan(b"-0b101").unwrap(),
+			number(true, b"101", None, None, 2)
+		);
+
+		assert!(scan(b"0x").is_err());
+		assert!(scan(b"0xfg").is_err());
+		assert!(scan(b"0b1_0").is_err());
+		assert!(scan(b"0x1.5").is_err());
+		assert_eq!(scan(b"0xffffffffffffffffff").unwrap().integer_value(), None);
+	}
+}
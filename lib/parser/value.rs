@@ -0,0 +1,342 @@
+//! Accessors and helpers for values
+
+#[cfg(feature = "containers")]
+use super::Map;
+use super::{Number, Value};
+use crate::{Error, ErrorKind, Result};
+
+macro_rules! impl_try_from_value {
+	($($ty:ty, $variant:ident, $name:literal);* $(;)?) => {
+		$(
+			impl TryFrom<Value> for $ty {
+				type Error = Error;
+
+				fn try_from(value: Value) -> Result<$ty> {
+					match value {
+						Value::$variant(inner) => Ok(inner),
+						other => Err(wrong_variant($name, &other)),
+					}
+				}
+			}
+
+			impl TryFrom<&Value> for $ty {
+				type Error = Error;
+
+				fn try_from(value: &Value) -> Result<$ty> {
+					match value {
+						Value::$variant(inner) => Ok(inner.clone()),
+						other => Err(wrong_variant($name, other)),
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl Value {
+	/// Returns the number this value holds, if it's an unsigned, signed or float value.
+	pub fn as_number(&self) -> Option<Number> {
+		match *self {
+			Value::Unsigned(unsigned) => Some(Number::Unsigned(unsigned)),
+			Value::Signed(signed) => Some(Number::Signed(signed)),
+			Value::Float(float) => Some(Number::Float(float)),
+			_ => None,
+		}
+	}
+
+	/// Returns the string this value holds, if it's a string value.
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Value::String(string) => Some(string),
+			_ => None,
+		}
+	}
+
+	/// Returns the integer this value holds, if it's an unsigned value.
+	pub fn as_unsigned(&self) -> Option<usize> {
+		match *self {
+			Value::Unsigned(unsigned) => Some(unsigned),
+			_ => None,
+		}
+	}
+
+	/// Returns the integer this value holds, if it's a signed value.
+	pub fn as_signed(&self) -> Option<isize> {
+		match *self {
+			Value::Signed(signed) => Some(signed),
+			_ => None,
+		}
+	}
+
+	/// Returns the float this value holds, if it's a float value.
+	pub fn as_float(&self) -> Option<f64> {
+		match *self {
+			Value::Float(float) => Some(float),
+			_ => None,
+		}
+	}
+
+	/// Returns the bool this value holds, if it's a bool value.
+	pub fn as_bool(&self) -> Option<bool> {
+		match *self {
+			Value::Bool(bool) => Some(bool),
+			_ => None,
+		}
+	}
+
+	/// Returns the elements of this value, if it's a list.
+	#[cfg(feature = "containers")]
+	pub fn as_list(&self) -> Option<&[Value]> {
+		match self {
+			Value::List(list) => Some(list),
+			_ => None,
+		}
+	}
+
+	/// Returns the entries of this value, if it's a map.
+	#[cfg(feature = "containers")]
+	pub fn as_map(&self) -> Option<&Map> {
+		match self {
+			Value::Map(map) => Some(map),
+			_ => None,
+		}
+	}
+
+	/// Creates a single byte value.
+	#[cfg(feature = "bytes")]
+	pub fn byte(byte: u8) -> Value {
+		Value::Byte(byte)
+	}
+
+	/// Creates a byte string value.
+	#[cfg(feature = "bytes")]
+	pub fn bytes(bytes: impl Into<Vec<u8>>) -> Value {
+		Value::Bytes(bytes.into())
+	}
+
+	/// Returns the byte this value holds, if it's a single byte value.
+	#[cfg(feature = "bytes")]
+	pub fn as_byte(&self) -> Option<u8> {
+		match *self {
+			Value::Byte(byte) => Some(byte),
+			_ => None,
+		}
+	}
+
+	/// Returns the bytes this value holds, if it's a byte string value.
+	#[cfg(feature = "bytes")]
+	pub fn as_bytes(&self) -> Option<&[u8]> {
+		match self {
+			Value::Bytes(bytes) => Some(bytes),
+			_ => None,
+		}
+	}
+
+	/// Returns a mutable reference to the value of `key`, if this is a map containing it.
+	#[cfg(feature = "containers")]
+	pub fn get_mut(&mut self, key: impl AsRef<[u8]>) -> Option<&mut Value> {
+		match self {
+			Value::Map(map) => map.get_mut(key.as_ref()),
+			_ => None,
+		}
+	}
+
+	/// Returns a mutable reference to the element at `index`, if this is a list long enough.
+	#[cfg(feature = "containers")]
+	pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Value> {
+		match self {
+			Value::List(list) => list.get_mut(index),
+			_ => None,
+		}
+	}
+
+	/// Calls `f` on this value and every value nested inside it, parents before their children.
+	///
+	/// The tree is walked with an explicit stack, so deep nesting can't overflow the call stack.
+	pub fn apply(&mut self, mut f: impl FnMut(&mut Value)) {
+		let mut stack = vec![self];
+
+		while let Some(value) = stack.pop() {
+			f(value);
+
+			match value {
+				#[cfg(feature = "containers")]
+				Value::List(list) => stack.extend(list.iter_mut().rev()),
+				#[cfg(feature = "containers")]
+				Value::Map(map) => stack.extend(map.values_mut()),
+				_ => {}
+			}
+		}
+	}
+
+	/// Normalizes this value and everything nested inside it, so that equal values have the same
+	/// representation: `-0.0` becomes `0.0`, every NaN becomes the same NaN, and with the
+	/// `ordered` feature, map keys are sorted. Without it, maps don't keep an order to sort.
+	///
+	/// Canonicalizing twice is the same as canonicalizing once.
+	pub fn canonicalize(&mut self) {
+		self.apply(|value| match value {
+			Value::Float(float) if *float == 0.0 => *float = 0.0,
+			Value::Float(float) if float.is_nan() => *float = f64::NAN,
+			#[cfg(all(feature = "containers", feature = "ordered"))]
+			Value::Map(map) => map.sort_unstable_keys(),
+			_ => {}
+		});
+	}
+
+	/// Concatenates every byte leaf in document order, skipping any other value.
+	///
+	/// Map entries are visited in the map's iteration order, which is only the document order
+	/// with the `ordered` feature. Without it, the bytes of a map with several entries come out
+	/// in an arbitrary order.
+	#[cfg(feature = "bytes")]
+	pub fn flatten_bytes(&self) -> Vec<u8> {
+		let mut out = vec![];
+		// Can't fail when skipping non-byte values.
+		let _ = self.collect_bytes(&mut out, true);
+		out
+	}
+
+	/// Like [`Value::flatten_bytes`], but fails with [`ErrorKind::WrongValue`] on the first
+	/// leaf that isn't bytes.
+	#[cfg(feature = "bytes")]
+	pub fn try_flatten_bytes(&self) -> Result<Vec<u8>> {
+		let mut out = vec![];
+		self.collect_bytes(&mut out, false)?;
+		Ok(out)
+	}
+
+	/// Appends every byte leaf to `out`, walking the tree with an explicit stack like
+	/// [`Value::apply`] so deep nesting can't overflow the call stack.
+	#[cfg(feature = "bytes")]
+	fn collect_bytes(&self, out: &mut Vec<u8>, skip_others: bool) -> Result<()> {
+		let mut stack = vec![self];
+
+		while let Some(value) = stack.pop() {
+			match value {
+				Value::Byte(byte) => out.push(*byte),
+				Value::Bytes(bytes) => out.extend_from_slice(bytes),
+				// Children are pushed last first, so that they're popped in order.
+				#[cfg(feature = "containers")]
+				Value::List(list) => stack.extend(list.iter().rev()),
+				#[cfg(feature = "containers")]
+				Value::Map(map) => {
+					let values: Vec<_> = map.values().collect();
+					stack.extend(values.into_iter().rev());
+				}
+				_ if skip_others => {}
+				other => {
+					return Err(Error::new(
+						ErrorKind::WrongValue,
+						format!("Expected bytes, found {other:?}"),
+						0,
+					))
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// The error for a conversion from a value of another variant.
+fn wrong_variant(expected: &str, found: &Value) -> Error {
+	Error::new(
+		ErrorKind::WrongValue,
+		format!("Expected {expected} value, found {found:?}"),
+		0,
+	)
+}
+
+impl_try_from_value! {
+	String, String, "a string";
+	usize, Unsigned, "an unsigned";
+	isize, Signed, "a signed";
+	f64, Float, "a float";
+	bool, Bool, "a bool";
+}
+
+impl From<&str> for Value {
+	fn from(string: &str) -> Value {
+		Value::String(string.to_string())
+	}
+}
+
+impl From<String> for Value {
+	fn from(string: String) -> Value {
+		Value::String(string)
+	}
+}
+
+impl From<usize> for Value {
+	fn from(unsigned: usize) -> Value {
+		Value::Unsigned(unsigned)
+	}
+}
+
+impl From<isize> for Value {
+	fn from(signed: isize) -> Value {
+		Value::Signed(signed)
+	}
+}
+
+impl From<f64> for Value {
+	fn from(float: f64) -> Value {
+		Value::Float(float)
+	}
+}
+
+impl From<bool> for Value {
+	fn from(bool: bool) -> Value {
+		Value::Bool(bool)
+	}
+}
+
+#[cfg(feature = "containers")]
+impl From<Vec<Value>> for Value {
+	fn from(list: Vec<Value>) -> Value {
+		Value::List(list)
+	}
+}
+
+#[cfg(feature = "bytes")]
+impl From<u8> for Value {
+	fn from(byte: u8) -> Value {
+		Value::Byte(byte)
+	}
+}
+
+#[cfg(feature = "bytes")]
+impl From<Vec<u8>> for Value {
+	fn from(bytes: Vec<u8>) -> Value {
+		Value::Bytes(bytes)
+	}
+}
+
+impl Number {
+	/// Converts the number to a float, rounding integers too large to be exact.
+	pub fn to_f64(self) -> f64 {
+		match self {
+			Number::Unsigned(unsigned) => unsigned as f64,
+			Number::Signed(signed) => signed as f64,
+			Number::Float(float) => float,
+		}
+	}
+
+	/// Converts the number to an integer, or `None` if it's a float with a fractional part or
+	/// out of range.
+	pub fn to_i128(self) -> Option<i128> {
+		match self {
+			Number::Unsigned(unsigned) => Some(unsigned as i128),
+			Number::Signed(signed) => Some(signed as i128),
+			Number::Float(float)
+				if float.fract() == 0.0
+					&& float >= i128::MIN as f64
+					&& float < i128::MAX as f64 =>
+			{
+				Some(float as i128)
+			}
+			Number::Float(_) => None,
+		}
+	}
+}
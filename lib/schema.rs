@@ -0,0 +1,35 @@
+//! Schemas describing the keys a message is expected to have.
+
+use std::collections::HashMap;
+
+use crate::{Message, Value};
+
+/// Describes the optional keys of a message, along with their default values.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+	/// Default values of the optional keys, used when a message doesn't set them.
+	pub defaults: HashMap<Vec<u8>, Value>,
+}
+
+impl Schema {
+	/// Create an empty schema.
+	pub fn new() -> Schema {
+		Schema::default()
+	}
+
+	/// Declares `key` as optional, defaulting to `default` when absent.
+	pub fn optional(mut self, key: impl Into<Vec<u8>>, default: Value) -> Schema {
+		self.defaults.insert(key.into(), default);
+		self
+	}
+
+	/// Inserts the default of every optional key missing from `message`.
+	pub fn apply_defaults(&self, message: &mut Message) {
+		for (key, default) in &self.defaults {
+			message
+				.0
+				.entry(key.clone())
+				.or_insert_with(|| default.clone());
+		}
+	}
+}
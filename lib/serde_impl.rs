@@ -0,0 +1,196 @@
+//! [`serde::Serialize`] for values and messages, and [`serde::Deserialize`] for values, behind
+//! the `serde` feature.
+//!
+//! Maps and messages become serde maps with their keys in sorted order (or in the order they
+//! were written, with the `ordered` feature). Keys are always written as strings, so that formats
+//! only taking string keys, like JSON, accept them. Keys that aren't valid UTF-8 have each of
+//! their invalid bytes written as a `\xNN` hex escape and their backslashes doubled, so that
+//! their bytes can be decoded back: `a\` followed by the byte `0xff` becomes `a\\\xff`.
+
+use std::fmt;
+
+use serde::{
+	de::{self, Visitor},
+	ser::SerializeMap,
+	Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{serializer::write_order, Map, Message, Value};
+
+impl Serialize for Value {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match self {
+			Value::String(string) => serializer.serialize_str(string),
+			Value::Unsigned(unsigned) => serializer.serialize_u64(*unsigned as u64),
+			Value::Signed(signed) => serializer.serialize_i64(*signed as i64),
+			Value::Float(float) => serializer.serialize_f64(*float),
+			Value::Bool(bool) => serializer.serialize_bool(*bool),
+			#[cfg(feature = "bytes")]
+			Value::Byte(byte) => serializer.serialize_u8(*byte),
+			#[cfg(feature = "bytes")]
+			Value::Bytes(bytes) => serializer.serialize_bytes(bytes),
+			#[cfg(feature = "containers")]
+			Value::List(list) => {
+				use serde::ser::SerializeSeq;
+
+				let mut seq = serializer.serialize_seq(Some(list.len()))?;
+
+				for value in list {
+					seq.serialize_element(value)?;
+				}
+
+				seq.end()
+			}
+			#[cfg(feature = "containers")]
+			Value::Map(map) => serialize_map(map, serializer),
+		}
+	}
+}
+
+impl Serialize for Message {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serialize_map(&self.0, serializer)
+	}
+}
+
+fn serialize_map<S: Serializer>(map: &Map, serializer: S) -> Result<S::Ok, S::Error> {
+	let entries = write_order(map);
+
+	let mut out = serializer.serialize_map(Some(entries.len()))?;
+
+	for (key, value) in entries {
+		out.serialize_entry(&Key(key), value)?;
+	}
+
+	out.end()
+}
+
+/// A map key, serialized as a string, with its invalid bytes escaped if it isn't valid UTF-8.
+struct Key<'a>(&'a [u8]);
+
+impl Serialize for Key<'_> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		if let Ok(key) = std::str::from_utf8(self.0) {
+			return serializer.serialize_str(key);
+		}
+
+		let mut key = String::new();
+
+		for chunk in self.0.utf8_chunks() {
+			key.push_str(&chunk.valid().replace('\\', "\\\\"));
+
+			for byte in chunk.invalid() {
+				key.push_str(&format!("\\x{byte:02x}"));
+			}
+		}
+
+		serializer.serialize_str(&key)
+	}
+}
+
+impl<'de> Deserialize<'de> for Value {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+		deserializer.deserialize_any(ValueVisitor)
+	}
+}
+
+/// Builds a [`Value`] out of any self-describing serde format. Unsigned integers become
+/// [`Value::Unsigned`] and signed ones [`Value::Signed`], as the format reports them.
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+	type Value = Value;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a YADIL value")
+	}
+
+	fn visit_bool<E: de::Error>(self, bool: bool) -> Result<Value, E> {
+		Ok(Value::Bool(bool))
+	}
+
+	fn visit_u64<E: de::Error>(self, unsigned: u64) -> Result<Value, E> {
+		usize::try_from(unsigned)
+			.map(Value::Unsigned)
+			.map_err(|_| E::custom("Value is out of range for unsigned"))
+	}
+
+	fn visit_i64<E: de::Error>(self, signed: i64) -> Result<Value, E> {
+		isize::try_from(signed)
+			.map(Value::Signed)
+			.map_err(|_| E::custom("Value is out of range for signed"))
+	}
+
+	fn visit_f64<E: de::Error>(self, float: f64) -> Result<Value, E> {
+		Ok(Value::Float(float))
+	}
+
+	fn visit_str<E: de::Error>(self, string: &str) -> Result<Value, E> {
+		Ok(Value::String(string.to_string()))
+	}
+
+	fn visit_string<E: de::Error>(self, string: String) -> Result<Value, E> {
+		Ok(Value::String(string))
+	}
+
+	#[cfg(feature = "bytes")]
+	fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Value, E> {
+		Ok(Value::Bytes(bytes.to_vec()))
+	}
+
+	#[cfg(feature = "bytes")]
+	fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Value, E> {
+		Ok(Value::Bytes(bytes))
+	}
+
+	#[cfg(feature = "containers")]
+	fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+		let mut list = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+
+		while let Some(value) = seq.next_element()? {
+			list.push(value);
+		}
+
+		Ok(Value::List(list))
+	}
+
+	#[cfg(feature = "containers")]
+	fn visit_map<A: de::MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+		let mut map = Map::with_capacity(access.size_hint().unwrap_or_default());
+
+		while let Some((KeyBuf(key), value)) = access.next_entry()? {
+			map.insert(key, value);
+		}
+
+		Ok(Value::Map(map))
+	}
+}
+
+/// A map key read from either a string or bytes.
+#[cfg(feature = "containers")]
+struct KeyBuf(Vec<u8>);
+
+#[cfg(feature = "containers")]
+impl<'de> Deserialize<'de> for KeyBuf {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<KeyBuf, D::Error> {
+		struct KeyVisitor;
+
+		impl Visitor<'_> for KeyVisitor {
+			type Value = KeyBuf;
+
+			fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+				formatter.write_str("a string or bytes key")
+			}
+
+			fn visit_str<E: de::Error>(self, key: &str) -> Result<KeyBuf, E> {
+				Ok(KeyBuf(key.as_bytes().to_vec()))
+			}
+
+			fn visit_bytes<E: de::Error>(self, key: &[u8]) -> Result<KeyBuf, E> {
+				Ok(KeyBuf(key.to_vec()))
+			}
+		}
+
+		deserializer.deserialize_any(KeyVisitor)
+	}
+}
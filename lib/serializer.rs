@@ -0,0 +1,611 @@
+//! Encoding of messages as YADIL text, the inverse of parsing.
+//!
+//! Keys are written in sorted order, so equal messages are always written the same way, or in
+//! the order they were written with the `ordered` feature. Floats
+//! are written with as many digits as needed to be parsed back exactly.
+
+use std::fmt;
+#[cfg(feature = "containers")]
+use std::io::Write;
+
+use crate::{Error, ErrorKind, Map, Message, Parser, Result, Value};
+
+/// Encode a message as compact YADIL text, which parses back into an equal message.
+///
+/// Fails with [`ErrorKind::WrongValue`] for values YADIL can't represent: empty keys and strings,
+/// and lists mixing data types or holding maps.
+pub fn to_bytes(message: &Message) -> Result<Vec<u8>> {
+	let mut out = vec![];
+
+	for (key, value) in write_order(&message.0) {
+		value.write(key, &mut out)?;
+	}
+
+	Ok(out)
+}
+
+/// Encode a message as readable YADIL text, with long data type names and each assignment on its
+/// own line. Map entries and nested lists are indented by `indent` spaces per level.
+///
+/// Raw values that aren't valid UTF-8 are written as byte lists. Fails like [`to_bytes`], and for
+/// such raw values inside lists.
+pub fn to_string_pretty(message: &Message, indent: usize) -> Result<String> {
+	write_pretty(message, indent, false)
+}
+
+/// Encode a message as readable YADIL text like [`to_string_pretty`], lining up the `=` of
+/// consecutive assignments at the same level. A map ends the run of assignments around it, and
+/// its entries are lined up on their own.
+pub fn to_string_aligned(message: &Message, indent: usize) -> Result<String> {
+	write_pretty(message, indent, true)
+}
+
+fn write_pretty(message: &Message, indent: usize, align: bool) -> Result<String> {
+	let mut out = vec![];
+	write_entries(&message.0, Some(indent), align, 0, &mut out)?;
+
+	String::from_utf8(out)
+		.map_err(|_| error("Raw values that aren't UTF-8 can't be written as a string"))
+}
+
+impl Value {
+	/// Writes an assignment of this value to `key` as compact YADIL text, like
+	/// [`to_bytes`] does for each entry of a message.
+	pub fn write(&self, key: &[u8], out: &mut Vec<u8>) -> Result<()> {
+		write_assign(self, key, None, None, 0, out)
+	}
+}
+
+/// Displays the value as the compact YADIL text of an assignment of it, without the data type,
+/// key and `;`: `a\;b` for the string `a;b`, `[1,2]` for a list, and `u@a=1;e` for a map.
+///
+/// Values that [`to_bytes`] would fail to write, like empty strings or lists mixing data types,
+/// have no YADIL text. They're displayed as a `<no YADIL text: ...>` placeholder giving the
+/// reason, so that displaying a value never fails.
+impl fmt::Display for Value {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut out = vec![];
+
+		match write_value(self, &mut out) {
+			Ok(()) => f.write_str(&String::from_utf8_lossy(&out)),
+			Err(err) => write!(f, "<no YADIL text: {}>", err.message),
+		}
+	}
+}
+
+/// Writes the part of a compact assignment after its `=` (or `:` for maps), without the `;`.
+fn write_value(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+	match value {
+		#[cfg(feature = "containers")]
+		Value::List(list) => {
+			list_type(list)?;
+			write_list(list, None, 0, out)
+		}
+		#[cfg(feature = "containers")]
+		Value::Map(map) => {
+			for (key, value) in write_order(map) {
+				value.write(key, out)?;
+			}
+
+			out.push(b'e');
+			Ok(())
+		}
+		scalar => write_scalar(scalar, false, out),
+	}
+}
+
+/// Writes the entries of a map, compact if `indent` is `None`, or else pretty and `depth` levels
+/// deep. With `align`, pretty assignments in a row that aren't maps line up their `=`.
+fn write_entries(
+	map: &Map,
+	indent: Option<usize>,
+	align: bool,
+	depth: usize,
+	out: &mut Vec<u8>,
+) -> Result<()> {
+	let entries = write_order(map);
+	let mut pads = vec![0; entries.len()];
+
+	if align && indent.is_some() {
+		let mut start = 0;
+
+		for run in entries.chunk_by(|(_, a), (_, b)| !is_map(a) && !is_map(b)) {
+			let widths = run
+				.iter()
+				.map(|(key, value)| key_width(value, key))
+				.collect::<Result<Vec<_>>>()?;
+			let max = widths.iter().copied().max().unwrap_or_default();
+
+			for (pad, width) in pads[start..].iter_mut().zip(widths) {
+				*pad = max - width;
+			}
+
+			start += run.len();
+		}
+	}
+
+	for ((key, value), pad) in entries.into_iter().zip(pads) {
+		write_assign(value, key, indent, align.then_some(pad), depth, out)?;
+	}
+
+	Ok(())
+}
+
+/// Writes an assignment, compact if `indent` is `None`, or else pretty and `depth` levels deep.
+/// Pretty assignments are padded by `align` spaces before their `=`, and map entries are lined
+/// up if it's set.
+fn write_assign(
+	value: &Value,
+	key: &[u8],
+	indent: Option<usize>,
+	align: Option<usize>,
+	depth: usize,
+	out: &mut Vec<u8>,
+) -> Result<()> {
+	let pretty = indent.is_some();
+	write_indent(indent, depth, out);
+
+	#[cfg(feature = "containers")]
+	if let Value::Map(map) = value {
+		out.extend_from_slice(if pretty { b"map @" } else { b"m@" });
+		write_key(key, out)?;
+		out.push(b':');
+
+		if pretty {
+			out.push(b'\n');
+		}
+
+		write_entries(map, indent, align.is_some(), depth + 1, out)?;
+
+		if pretty {
+			write_indent(indent, depth, out);
+			out.extend_from_slice(b"end;\n");
+		} else {
+			out.extend_from_slice(b"e;");
+		}
+
+		return Ok(());
+	}
+
+	// Pretty text has to be a string, so raw values that aren't UTF-8 become byte lists.
+	#[cfg(feature = "bytes")]
+	if let Value::Bytes(bytes) = value {
+		if pretty && std::str::from_utf8(bytes).is_err() {
+			out.extend_from_slice(b"bytes @");
+			write_key(key, out)?;
+			write_padding(align, out);
+			out.extend_from_slice(b" = [");
+
+			for (i, byte) in bytes.iter().enumerate() {
+				if i > 0 {
+					out.extend_from_slice(b", ");
+				}
+
+				out.extend_from_slice(byte.to_string().as_bytes());
+			}
+
+			out.extend_from_slice(b"];\n");
+			return Ok(());
+		}
+	}
+
+	out.extend_from_slice(data_type(value, pretty)?.as_bytes());
+	out.extend_from_slice(if pretty { b" @" } else { b"@" });
+	write_key(key, out)?;
+	write_padding(align, out);
+
+	// Raw values keep the whitespace after their `=`, so there's no space there.
+	match value {
+		#[cfg(feature = "bytes")]
+		Value::Bytes(_) if pretty => out.extend_from_slice(b" ="),
+		_ if pretty => out.extend_from_slice(b" = "),
+		_ => out.push(b'='),
+	}
+
+	match value {
+		#[cfg(feature = "containers")]
+		Value::List(list) => write_list(list, indent, depth, out)?,
+		scalar => write_scalar(scalar, false, out)?,
+	}
+
+	out.push(b';');
+
+	if pretty {
+		out.push(b'\n');
+	}
+
+	Ok(())
+}
+
+/// Writes the indentation of a pretty line `depth` levels deep.
+fn write_indent(indent: Option<usize>, depth: usize, out: &mut Vec<u8>) {
+	if let Some(indent) = indent {
+		out.resize(out.len() + indent * depth, b' ');
+	}
+}
+
+/// Writes the spaces lining up the `=` of a pretty assignment.
+fn write_padding(align: Option<usize>, out: &mut Vec<u8>) {
+	out.resize(out.len() + align.unwrap_or_default(), b' ');
+}
+
+/// Whether a value is a map, which ends a run of lined up assignments.
+fn is_map(value: &Value) -> bool {
+	#[cfg(feature = "containers")]
+	if let Value::Map(_) = value {
+		return true;
+	}
+
+	#[cfg(not(feature = "containers"))]
+	let _ = value;
+	false
+}
+
+/// The width in characters of the `type @key` start of a pretty assignment.
+fn key_width(value: &Value, key: &[u8]) -> Result<usize> {
+	#[allow(unused_mut)]
+	let mut start = data_type(value, true)?.into_bytes();
+
+	#[cfg(feature = "bytes")]
+	if let Value::Bytes(bytes) = value {
+		if std::str::from_utf8(bytes).is_err() {
+			start = b"bytes".to_vec();
+		}
+	}
+
+	start.extend_from_slice(b" @");
+	write_key(key, &mut start)?;
+	Ok(String::from_utf8_lossy(&start).chars().count())
+}
+
+/// The entries of a map in the order they're written: sorted by key, or as they are with the
+/// `ordered` feature.
+pub(crate) fn write_order(map: &Map) -> Vec<(&Vec<u8>, &Value)> {
+	#[allow(unused_mut)]
+	let mut entries: Vec<_> = map.iter().collect();
+
+	#[cfg(not(feature = "ordered"))]
+	entries.sort_unstable_by_key(|(key, _)| *key);
+
+	entries
+}
+
+/// Writes a list assignment to `out` one element at a time, so that a large list never has to be
+/// held in memory as a [`Value::List`].
+///
+/// The start of the assignment, like `l.u@key=[`, is written as the writer is created, and its
+/// closing `];` by [`ListWriter::finish`]. Dropping the writer closes the list too, but ignores
+/// any error doing so. Elements are written as compact YADIL text, like [`to_bytes`] does.
+#[cfg(feature = "containers")]
+pub struct ListWriter<W: Write> {
+	/// `None` once the list is closed.
+	out: Option<W>,
+	/// The short and long data type of the elements.
+	element: (&'static str, &'static str),
+	/// Whether an element was written, so the next one needs a `,` before it.
+	started: bool,
+	/// The text of the element being written, kept to reuse its memory.
+	buf: Vec<u8>,
+}
+
+#[cfg(feature = "containers")]
+impl<W: Write> ListWriter<W> {
+	/// Starts writing a list assigned to `key`, whose elements have the data type `element`,
+	/// short or long, like `u` or `uint`.
+	///
+	/// Fails with [`ErrorKind::WrongValue`] if `element` isn't the data type of a value that
+	/// isn't a list or map, or if `key` is empty, and with [`ErrorKind::Io`] if writing fails.
+	pub fn new(mut out: W, key: &[u8], element: &str) -> Result<ListWriter<W>> {
+		let element = match element {
+			"s" | "str" => ("s", "str"),
+			"u" | "uint" => ("u", "uint"),
+			"i" | "sint" => ("i", "sint"),
+			"f" | "float" => ("f", "float"),
+			"b" | "bool" => ("b", "bool"),
+			#[cfg(feature = "bytes")]
+			"y" | "byte" => ("y", "byte"),
+			#[cfg(feature = "bytes")]
+			"r" | "raw" => ("r", "raw"),
+			other => return Err(error(format!("`{other}` isn't a list element type"))),
+		};
+
+		let mut buf = format!("l.{}@", element.0).into_bytes();
+		write_key(key, &mut buf)?;
+		buf.extend_from_slice(b"=[");
+		out.write_all(&buf).map_err(io_error)?;
+
+		Ok(ListWriter {
+			out: Some(out),
+			element,
+			started: false,
+			buf,
+		})
+	}
+
+	/// Writes the next element of the list.
+	///
+	/// Fails with [`ErrorKind::WrongValue`] if it doesn't have the data type of the list, or
+	/// can't be written in a list (like an empty string), and with [`ErrorKind::Io`] if writing
+	/// fails.
+	pub fn push(&mut self, value: &Value) -> Result<()> {
+		let Some(out) = self.out.as_mut() else {
+			unreachable!("only finishing the list closes it")
+		};
+
+		self.buf.clear();
+
+		if self.started {
+			self.buf.push(b',');
+		}
+
+		write_scalar(value, true, &mut self.buf)?;
+
+		if scalar_type(value) != self.element {
+			return Err(error("List elements must all have the same data type"));
+		}
+
+		out.write_all(&self.buf).map_err(io_error)?;
+		self.started = true;
+		Ok(())
+	}
+
+	/// Closes the list with `];`, and returns the writer it was written to.
+	pub fn finish(mut self) -> Result<W> {
+		let Some(mut out) = self.out.take() else {
+			unreachable!("only finishing the list closes it")
+		};
+
+		out.write_all(b"];").map_err(io_error)?;
+		Ok(out)
+	}
+}
+
+#[cfg(feature = "containers")]
+impl<W: Write> Drop for ListWriter<W> {
+	fn drop(&mut self) {
+		if let Some(out) = self.out.as_mut() {
+			let _ = out.write_all(b"];");
+		}
+	}
+}
+
+#[cfg(feature = "containers")]
+fn io_error(err: std::io::Error) -> Error {
+	Error::new(ErrorKind::Io, err.to_string(), 0)
+}
+
+fn error(message: impl Into<String>) -> Error {
+	Error::new(ErrorKind::WrongValue, message.into(), 0)
+}
+
+fn write_key(key: &[u8], out: &mut Vec<u8>) -> Result<()> {
+	if key.is_empty() {
+		return Err(error("Empty keys can't be written"));
+	}
+
+	// Whitespace is skipped anywhere in keys, so it's always escaped.
+	write_escaped(key, &Parser::IDENT_ESCAPES, Escaping::default(), out);
+	Ok(())
+}
+
+/// How bytes are escaped, on top of the ones that would end them.
+#[derive(Clone, Copy, Default)]
+struct Escaping {
+	/// Escape leading whitespace, which would be skipped.
+	leading: bool,
+	/// Escape trailing whitespace, which would be trimmed.
+	trailing: bool,
+	/// Write newlines, tabs and carriage returns as `\n`, `\t` and `\r`.
+	named: bool,
+}
+
+/// Writes `bytes`, putting a backslash before each byte of `escapes` and as `escaping` says.
+fn write_escaped(bytes: &[u8], escapes: &[u8], escaping: Escaping, out: &mut Vec<u8>) {
+	let is_space = |byte: &u8| Parser::IGNORE_BYTES.contains(byte);
+	let leading = bytes.iter().take_while(|byte| is_space(byte)).count();
+	let trailing = bytes.len() - bytes.iter().rev().take_while(|byte| is_space(byte)).count();
+
+	for (i, &byte) in bytes.iter().enumerate() {
+		let named = match byte {
+			b'\n' => b'n',
+			b'\t' => b't',
+			b'\r' => b'r',
+			_ => 0,
+		};
+
+		if escaping.named && named != 0 {
+			out.extend([b'\\', named]);
+			continue;
+		}
+
+		let edge = (escaping.leading && i < leading) || (escaping.trailing && i >= trailing);
+
+		if escapes.contains(&byte) || edge {
+			out.push(b'\\');
+		}
+
+		out.push(byte);
+	}
+}
+
+/// The data type of a value, short or long. Lists get their full type, like `l.l.u`.
+fn data_type(value: &Value, long: bool) -> Result<String> {
+	Ok(match value {
+		#[cfg(feature = "containers")]
+		Value::List(list) => {
+			let ListType { levels, element } = list_type(list)?;
+			let (list, element) = match long {
+				true => ("list.", element.map_or("str", |(_, long)| long)),
+				false => ("l.", element.map_or("s", |(short, _)| short)),
+			};
+
+			format!("{}{element}", list.repeat(levels))
+		}
+		#[cfg(feature = "containers")]
+		Value::Map(_) if long => "map".into(),
+		#[cfg(feature = "containers")]
+		Value::Map(_) => "m".into(),
+		scalar => {
+			let (short, long_name) = scalar_type(scalar);
+			if long { long_name } else { short }.into()
+		}
+	})
+}
+
+/// The short and long data type of a scalar value.
+fn scalar_type(value: &Value) -> (&'static str, &'static str) {
+	match value {
+		Value::String(_) => ("s", "str"),
+		Value::Unsigned(_) => ("u", "uint"),
+		Value::Signed(_) => ("i", "sint"),
+		Value::Float(_) => ("f", "float"),
+		Value::Bool(_) => ("b", "bool"),
+		#[cfg(feature = "bytes")]
+		Value::Byte(_) => ("y", "byte"),
+		#[cfg(feature = "bytes")]
+		Value::Bytes(_) => ("r", "raw"),
+		#[cfg(feature = "containers")]
+		Value::List(_) | Value::Map(_) => unreachable!("containers aren't scalars"),
+	}
+}
+
+/// Writes a scalar value, escaping strings and raw bytes for the top level or for a list.
+fn write_scalar(value: &Value, in_list: bool, out: &mut Vec<u8>) -> Result<()> {
+	// Values skip leading whitespace, and list elements trailing whitespace too. Raw values
+	// keep everything, and don't take named escapes.
+	let (escapes, escaping): (&[u8], _) = match (in_list, value) {
+		(true, _) => (
+			b",];\\",
+			Escaping {
+				leading: true,
+				trailing: true,
+				named: true,
+			},
+		),
+		#[cfg(feature = "bytes")]
+		(false, Value::Bytes(_)) => (b";\\", Escaping::default()),
+		(false, _) => (
+			b";\\",
+			Escaping {
+				leading: true,
+				named: true,
+				..Escaping::default()
+			},
+		),
+	};
+
+	match value {
+		Value::String(string) if string.is_empty() => {
+			return Err(error("Empty strings can't be written"))
+		}
+		Value::String(string) => write_escaped(string.as_bytes(), escapes, escaping, out),
+		Value::Unsigned(unsigned) => out.extend_from_slice(unsigned.to_string().as_bytes()),
+		Value::Signed(signed) => out.extend_from_slice(signed.to_string().as_bytes()),
+		Value::Float(float) if float.is_nan() => out.extend_from_slice(b"nan"),
+		// Debug formatting switches to an exponent for very large and small floats.
+		Value::Float(float) => out.extend_from_slice(format!("{float:?}").as_bytes()),
+		Value::Bool(bool) => out.extend_from_slice(bool.to_string().as_bytes()),
+		#[cfg(feature = "bytes")]
+		Value::Byte(byte) => out.extend_from_slice(byte.to_string().as_bytes()),
+		#[cfg(feature = "bytes")]
+		Value::Bytes(bytes) if bytes.is_empty() && in_list => {
+			return Err(error("Empty raw list elements can't be written"))
+		}
+		#[cfg(feature = "bytes")]
+		Value::Bytes(bytes) => write_escaped(bytes, escapes, escaping, out),
+		#[cfg(feature = "containers")]
+		Value::List(_) | Value::Map(_) => return Err(error("Expected a scalar value")),
+	}
+
+	Ok(())
+}
+
+/// Writes a list. Pretty lists of lists put each inner list on its own line, one level deeper.
+#[cfg(feature = "containers")]
+fn write_list(
+	list: &[Value],
+	indent: Option<usize>,
+	depth: usize,
+	out: &mut Vec<u8>,
+) -> Result<()> {
+	let nested = indent.is_some() && list.iter().any(|value| matches!(value, Value::List(_)));
+	out.push(b'[');
+
+	for (i, value) in list.iter().enumerate() {
+		if i > 0 {
+			out.push(b',');
+		}
+
+		if nested {
+			out.push(b'\n');
+			write_indent(indent, depth + 1, out);
+		} else if i > 0 && indent.is_some() {
+			out.push(b' ');
+		}
+
+		match value {
+			Value::List(inner) => write_list(inner, indent, depth + 1, out)?,
+			scalar => write_scalar(scalar, true, out)?,
+		}
+	}
+
+	if nested {
+		out.push(b'\n');
+		write_indent(indent, depth, out);
+	}
+
+	out.push(b']');
+	Ok(())
+}
+
+/// The nesting and element type of a list.
+#[cfg(feature = "containers")]
+struct ListType {
+	/// How many lists deep the elements are. Only a lower bound if there are no elements.
+	levels: usize,
+	/// The short and long data type of the elements, if there are any.
+	element: Option<(&'static str, &'static str)>,
+}
+
+/// Finds the type of a list, failing if its elements don't all have the same data type and
+/// nesting (empty lists fit at any nesting).
+#[cfg(feature = "containers")]
+fn list_type(list: &[Value]) -> Result<ListType> {
+	let mut found = ListType {
+		levels: 1,
+		element: None,
+	};
+
+	for value in list {
+		let other = match value {
+			Value::List(inner) => {
+				let inner = list_type(inner)?;
+				ListType {
+					levels: inner.levels + 1,
+					element: inner.element,
+				}
+			}
+			Value::Map(_) => return Err(error("Lists of maps can't be written")),
+			scalar => ListType {
+				levels: 1,
+				element: Some(scalar_type(scalar)),
+			},
+		};
+
+		found = match (found.element, other.element) {
+			(Some(a), Some(b)) if a != b || found.levels != other.levels => {
+				return Err(error("List elements must all have the same data type"))
+			}
+			(Some(_), None) if other.levels <= found.levels => found,
+			(None, Some(_)) if found.levels <= other.levels => other,
+			(None, None) => ListType {
+				levels: found.levels.max(other.levels),
+				element: None,
+			},
+			(Some(_), Some(_)) => found,
+			_ => return Err(error("List elements must all have the same nesting")),
+		};
+	}
+
+	Ok(found)
+}
@@ -18,8 +18,10 @@ fn main() {
 	let message = match parse(&bytes) {
 		Ok(message) => message,
 		Err(err) => {
-			let (line, col) = yadil::index_to_line_col(&bytes, err.index);
-			eprintln!("Error parsing file at ({path}:{line}:{col}): {err:#?}");
+			eprintln!(
+				"Error parsing file at ({}): {err}",
+				location(&path, err.line, err.col)
+			);
 			return;
 		}
 	};
@@ -32,3 +34,11 @@ fn main() {
 
 	println!("{:#?}", utf8_map);
 }
+
+/// Formats an error location as `"path":line:col`.
+///
+/// The path is quoted so that colons inside it (e.g. `C:\` on Windows) can't be confused with
+/// the line and column separators.
+fn location(path: &str, line: usize, col: usize) -> String {
+	format!("{path:?}:{line}:{col}")
+}
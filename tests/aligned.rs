@@ -0,0 +1,54 @@
+//! Readable output with the `=` of assignments lined up.
+
+use yadil::Value;
+
+#[test]
+fn aligns_assignments() {
+	let message = yadil::parse(b"s@name=demo;u@port=8080;b@tls=t;").unwrap();
+
+	assert_eq!(
+		yadil::to_string_pretty(&message, 4).unwrap(),
+		"str @name = demo;\nuint @port = 8080;\nbool @tls = true;\n"
+	);
+	let aligned = yadil::to_string_aligned(&message, 4).unwrap();
+	assert_eq!(
+		aligned,
+		"str @name  = demo;\nuint @port = 8080;\nbool @tls  = true;\n"
+	);
+	assert_eq!(yadil::parse(aligned.as_bytes()).unwrap(), message);
+}
+
+#[cfg(all(feature = "containers", feature = "ordered"))]
+#[test]
+fn maps_reset_alignment() {
+	let message =
+		yadil::parse(b"s@a=1;s@long_key=2;m@map:u@x=1;s@longer_key=2;e;s@b=3;f@c=0.5;").unwrap();
+	let aligned = yadil::to_string_aligned(&message, 2).unwrap();
+
+	assert_eq!(
+		aligned,
+		"\
+str @a        = 1;
+str @long_key = 2;
+map @map:
+  uint @x         = 1;
+  str @longer_key = 2;
+end;
+str @b   = 3;
+float @c = 0.5;
+"
+	);
+	assert_eq!(yadil::parse(aligned.as_bytes()).unwrap(), message);
+}
+
+#[test]
+fn aligns_escaped_keys_by_characters() {
+	let mut message = yadil::Message::new();
+	message.insert("a b", Value::from(2usize));
+	message.insert("é", Value::from(1usize));
+
+	assert_eq!(
+		yadil::to_string_aligned(&message, 4).unwrap(),
+		"uint @a\\ b = 2;\nuint @é    = 1;\n"
+	);
+}
@@ -0,0 +1,88 @@
+//! Messages parsed into a bump arena.
+
+#![cfg(feature = "arena")]
+
+use bumpalo::Bump;
+use yadil::{ArenaValue, ErrorKind};
+
+#[test]
+fn parses_into_a_reset_arena() {
+	let mut arena = Bump::new();
+	let inputs = [
+		&b"s@name=first;u@port=80;"[..],
+		b"s@name=second;i@offset=-1;s@name=last;",
+		b"f@ratio=0.5;b@on=t;",
+	];
+
+	for input in inputs {
+		arena.reset();
+		let message = yadil::parse_in(input, &arena).unwrap();
+		let expected = yadil::parse(input).unwrap();
+
+		assert_eq!(message.entries().len(), expected.iter().count());
+		for (key, value) in message.entries() {
+			assert_eq!(Some(&value.to_value()), expected.get(key));
+		}
+	}
+
+	// Only the last assignment of a repeated key is kept.
+	arena.reset();
+	let message = yadil::parse_in(inputs[1], &arena).unwrap();
+	assert_eq!(message.get("name"), Some(&ArenaValue::String("last")));
+	assert_eq!(message.get("offset"), Some(&ArenaValue::Signed(-1)));
+	assert_eq!(message.get("missing"), None);
+
+	let err = yadil::parse_in(b"u@a=1;u@b=x;", &arena).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+}
+
+#[cfg(all(feature = "bytes", feature = "containers"))]
+#[test]
+fn nests_in_the_arena() {
+	let arena = Bump::new();
+	let message = yadil::parse_in(b"m@m:l.l.u@grid=[[1], []];x@x=[1, 2];e;", &arena).unwrap();
+
+	let Some(ArenaValue::Map(map)) = message.get("m") else {
+		panic!("expected a map");
+	};
+	assert_eq!(
+		map,
+		&[
+			(
+				&b"grid"[..],
+				ArenaValue::List(&[
+					ArenaValue::List(&[ArenaValue::Unsigned(1)]),
+					ArenaValue::List(&[])
+				])
+			),
+			(&b"x"[..], ArenaValue::Bytes(&[1, 2])),
+		]
+	);
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn keeps_the_last_value_in_written_order() {
+	let arena = Bump::new();
+	let input = (0..1000)
+		.rev()
+		.map(|i| format!("u@k{i}={i};"))
+		.chain(["m@m:s@b=1;s@a=2;s@b=3;e;u@k0=7;".to_string()])
+		.collect::<String>();
+	let message = yadil::parse_in(input.as_bytes(), &arena).unwrap();
+
+	assert_eq!(message.entries().len(), 1001);
+	assert_eq!(message.entries()[0].0, b"k999");
+	assert_eq!(message.get("k0"), Some(&ArenaValue::Unsigned(7)));
+	assert_eq!(message.get("k500"), Some(&ArenaValue::Unsigned(500)));
+	assert_eq!(
+		message.get("m"),
+		Some(&ArenaValue::Map(&[
+			(&b"b"[..], ArenaValue::String("3")),
+			(&b"a"[..], ArenaValue::String("2")),
+		]))
+	);
+
+	let err = yadil::parse_in(b"m@m:u@a=1;", &arena).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,58 @@
+//! The binary encoding of messages.
+
+use yadil::{ErrorKind, Message, Value};
+
+#[test]
+fn round_trips_every_value() {
+	let mut message = Message::new();
+	message.insert("string", "text");
+	message.insert("unsigned", usize::MAX);
+	message.insert("signed", isize::MIN);
+	message.insert("negative", -1isize);
+	message.insert("float", Value::Float(-1.5));
+	message.insert("false", false);
+	message.insert("true", true);
+
+	#[cfg(feature = "bytes")]
+	{
+		message.insert("byte", Value::Byte(0xff));
+		message.insert("bytes", Value::Bytes(vec![0, 1, 0x80]));
+	}
+
+	#[cfg(feature = "containers")]
+	{
+		let mut inner = yadil::Map::new();
+		inner.insert(b"list".to_vec(), Value::List(vec![Value::from(1usize)]));
+		inner.insert(b"empty".to_vec(), Value::Map(yadil::Map::new()));
+
+		message.insert(
+			"list",
+			Value::List(vec![Value::List(vec![]), Value::Map(inner.clone())]),
+		);
+		message.insert("map", Value::Map(inner));
+	}
+
+	assert_eq!(
+		yadil::from_binary(&yadil::to_binary(&message)).unwrap(),
+		message
+	);
+}
+
+#[test]
+fn truncated_varint_fails() {
+	let mut bytes = yadil::to_binary(&[("k", usize::MAX)].into_iter().collect());
+	bytes.pop();
+
+	let err = yadil::from_binary(&bytes).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::UnexpectedEof);
+	assert_eq!(err.index, bytes.len());
+}
+
+#[test]
+fn unknown_tag_fails() {
+	// One entry, with the 1-byte key `k` and a value tagged 0xff.
+	let err = yadil::from_binary(&[1, 1, b'k', 0xff]).unwrap_err();
+
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+	assert_eq!(err.message, "Unknown binary tag `255`");
+}
@@ -0,0 +1,86 @@
+//! Parsing of assignments that borrow their strings from the input.
+
+use std::borrow::Cow;
+
+use yadil::{BorrowedAssign, BorrowedValue, ErrorKind, Parser, ParserOptions, Value};
+
+fn assigns(input: &[u8]) -> Vec<BorrowedAssign<'_>> {
+	let mut parser = Parser::new(input);
+	let mut assigns = vec![];
+
+	while let Some(assign) = parser.next_borrowed().unwrap() {
+		assigns.push(assign);
+	}
+
+	assigns
+}
+
+#[test]
+fn plain_strings_are_borrowed() {
+	let assigns = assigns(b"s@name=demo;\ns@greeting=hello, world;");
+
+	for BorrowedAssign(key, value) in &assigns {
+		assert!(matches!(key, Cow::Borrowed(_)), "{key:?} was copied");
+		assert!(
+			matches!(value, BorrowedValue::String(Cow::Borrowed(_))),
+			"{value:?} was copied"
+		);
+	}
+
+	assert_eq!(
+		assigns[1].1,
+		BorrowedValue::String(Cow::Borrowed("hello, world"))
+	);
+}
+
+#[test]
+fn escaped_strings_are_decoded() {
+	let assigns = assigns(b"s@a\\=b= one;s@line=one\\ntwo;u@n=1;");
+
+	// `Cow` compares by content, so each variant is checked on its own.
+	assert!(matches!(&assigns[0].0, Cow::Owned(key) if key == b"a=b"));
+	assert!(matches!(&assigns[0].1, BorrowedValue::String(Cow::Owned(value)) if value == "one"));
+	assert!(matches!(&assigns[1].0, Cow::Borrowed(b"line")));
+	assert!(
+		matches!(&assigns[1].1, BorrowedValue::String(Cow::Owned(value)) if value == "one\ntwo")
+	);
+	assert!(matches!(&assigns[2].0, Cow::Owned(key) if key == b"n"));
+	assert_eq!(assigns[2].1, BorrowedValue::Other(Value::Unsigned(1)));
+	assert_eq!(assigns.len(), 3);
+}
+
+#[test]
+fn strict_idents_are_checked() {
+	let options = ParserOptions::default().strict_idents(true);
+	let mut parser = Parser::with_options(b"s@bell\x07key=x;", options);
+
+	assert_eq!(
+		parser.next_borrowed().unwrap_err().kind,
+		ErrorKind::UnexpectedChar
+	);
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn outlive_the_input_once_owned() {
+	let input = b"s@name=demo;m@server:l.l.u@grid=[[1, 2], []];s@host=local;e;".to_vec();
+	let owned: Vec<_> = assigns(&input)
+		.into_iter()
+		.map(BorrowedAssign::into_owned)
+		.collect();
+	let expected = yadil::parse(&input).unwrap();
+	drop(input);
+
+	for yadil::Assign(key, value) in &owned {
+		assert_eq!(expected.get(key), Some(value));
+	}
+
+	assert_eq!(
+		owned[0],
+		yadil::Assign(b"name".to_vec(), Value::from("demo"))
+	);
+	assert_eq!(
+		BorrowedValue::String(Cow::Borrowed("x")).into_owned(),
+		Value::from("x")
+	);
+}
@@ -0,0 +1,93 @@
+//! Parsing bounded to the first bytes of the input.
+
+use yadil::{ErrorKind, Value};
+
+const INPUT: &[u8] = b"u@a=1;s@b=hello;u@c=3;";
+
+#[test]
+fn budget_splitting_an_assignment() {
+	let (message, consumed) = yadil::parse_upto(INPUT, 10).unwrap();
+
+	assert_eq!(consumed, 6);
+	assert_eq!(message.get("a"), Some(&Value::Unsigned(1)));
+	assert_eq!(message.get("b"), None);
+}
+
+#[test]
+fn budget_on_an_assignment_boundary() {
+	let (message, consumed) = yadil::parse_upto(INPUT, 16).unwrap();
+
+	assert_eq!(consumed, 16);
+	assert_eq!(message.get("b"), Some(&Value::from("hello")));
+	assert_eq!(message.get("c"), None);
+	assert_eq!(
+		yadil::parse_upto(INPUT, INPUT.len()).unwrap().1,
+		INPUT.len()
+	);
+}
+
+#[test]
+fn resumes_from_consumed() {
+	let (mut message, consumed) = yadil::parse_upto(INPUT, 10).unwrap();
+	let (rest, rest_consumed) = yadil::parse_upto(&INPUT[consumed..], 100).unwrap();
+
+	message.0.extend(rest.0);
+	assert_eq!(consumed + rest_consumed, INPUT.len());
+	assert_eq!(message, yadil::parse(INPUT).unwrap());
+}
+
+#[test]
+fn errors_past_the_budget_are_not_read() {
+	let (message, consumed) = yadil::parse_upto(b"u@a=1;u@b=x;", 6).unwrap();
+	assert_eq!((message.get("a"), consumed), (Some(&Value::Unsigned(1)), 6));
+
+	let err = yadil::parse_upto(b"u@a=x;u@b=1;", 100).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+}
+
+#[test]
+fn step_limit_stops_long_messages() {
+	let input = "u@a=1;".repeat(1000);
+	let err = yadil::parse_with_step_limit(input.as_bytes(), 100).unwrap_err();
+
+	assert_eq!(err.kind, ErrorKind::StepLimitExceeded);
+	assert_eq!(err.index, 100);
+	assert!(yadil::parse_with_step_limit(input.as_bytes(), input.len()).is_ok());
+}
+
+#[test]
+fn step_limit_cutting_a_token() {
+	let err = yadil::parse_with_step_limit("s@a=\\u{1F600};".as_bytes(), 10).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::StepLimitExceeded);
+
+	let err = yadil::parse_with_step_limit(b"u@a=x;u@b=1;", 100).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+}
+
+#[test]
+fn step_limit_counts_trailing_trivia() {
+	let message = yadil::parse_with_step_limit(b"u@a=1;   ", 9).unwrap();
+	assert_eq!(message.get("a"), Some(&Value::Unsigned(1)));
+	let message = yadil::parse_with_step_limit(b"u@a=1; # a comment # \0u@b=2;", 21).unwrap();
+	assert_eq!(message.get("b"), None);
+	assert!(yadil::parse_with_step_limit(b"u@a=1;\0u@b=2;", 8).is_ok());
+
+	// Trivia past the limit isn't read, even if the message would end after it.
+	for input in [
+		&b"u@a=1;   "[..],
+		b"u@a=1; # a comment # \n",
+		b"u@a=1; # cut # u@b=2;",
+	] {
+		let err = yadil::parse_with_step_limit(input, 8).unwrap_err();
+		assert_eq!(err.kind, ErrorKind::StepLimitExceeded);
+		assert_eq!(err.index, 8);
+	}
+}
+
+#[test]
+fn step_limit_stops_long_trailing_comments() {
+	let input = format!("u@a=1; #{}#", "long comment ".repeat(100_000));
+	let err = yadil::parse_with_step_limit(input.as_bytes(), 6).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::StepLimitExceeded);
+	assert!(yadil::parse_with_step_limit(input.as_bytes(), input.len()).is_ok());
+}
@@ -0,0 +1,22 @@
+//! The command-line binary.
+
+use std::{fs, process::Command};
+
+#[test]
+fn quotes_paths_in_error_locations() {
+	// A colon in the path can't be told apart from the line and column without quotes.
+	let dir = std::env::temp_dir().join(format!("yadil-cli-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let path = dir.join("C:invalid.yd");
+	fs::write(&path, b"u@a=1;\nu@b=x;").unwrap();
+
+	let output = Command::new(env!("CARGO_BIN_EXE_yadil"))
+		.arg(&path)
+		.output()
+		.unwrap();
+	fs::remove_dir_all(&dir).unwrap();
+
+	let stderr = String::from_utf8(output.stderr).unwrap();
+	let location = format!("({:?}:2:5)", path.to_str().unwrap());
+	assert!(stderr.contains(&location), "{stderr}");
+}
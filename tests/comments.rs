@@ -0,0 +1,29 @@
+//! Comments collected with their spans.
+
+#[test]
+fn collects_comments() {
+	let input = b"#!/usr/bin/env yadil\nu@a=1; # one #s@b=x#y;#\\# two#\n##";
+	let comments = yadil::collect_comments(input).unwrap();
+
+	assert_eq!(
+		comments,
+		[
+			// The shebang line, whose body starts after its `#`.
+			(0..20, b"!/usr/bin/env yadil".to_vec()),
+			(28..35, b" one ".to_vec()),
+			// The `#` in the value of `b` isn't a comment, and `\#` keeps a `#` in one.
+			(43..51, b"# two".to_vec()),
+			(52..54, vec![]),
+		]
+	);
+
+	for (span, _) in &comments {
+		assert_eq!(input[span.start], b'#');
+	}
+}
+
+#[test]
+fn invalid_messages_fail() {
+	assert!(yadil::collect_comments(b"# fine # u@a=x;").is_err());
+	assert_eq!(yadil::collect_comments(b"u@a=1;").unwrap(), []);
+}
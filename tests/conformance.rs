@@ -0,0 +1,257 @@
+//! Conformance suite for the parser.
+//!
+//! Every `tests/conformance/*.yd` file is parsed and its rendering compared against the sibling
+//! `.debug` file. Every `tests/conformance/invalid/*.yd` file must fail to parse with the error
+//! kind written in the sibling `.kind` file, with a message containing the sibling `.message`
+//! file if there is one, covering the `start..end` span in the sibling `.span` file if there is
+//! one, and at the `line:col` in the sibling `.position` file if there is one. Valid cases must
+//! also parse back the same after [`yadil::to_bytes`], [`yadil::to_string_pretty`] and
+//! [`yadil::to_string_aligned`], and the pretty text must give the sibling `.pretty` file
+//! (indenting by 4) if there is one. Adding a regression case only takes a new file pair.
+//!
+//! Cases can start with header comments, one per line:
+//! - `# feature: <name> #` only runs the case when that cargo feature is on, and
+//!   `# feature: !<name> #` only when it's off.
+//! - `# options: <option>, ... #` parses the case with those [`ParserOptions`], written as the
+//!   field name for flags (`numeric_bools`) and as `field=variant` otherwise
+//!   (`repeated_keys=error`).
+
+use std::{
+	fmt::Write,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use yadil::{Map, Parser, ParserOptions, RepeatedKeys};
+
+fn cases(dir: &Path) -> Vec<PathBuf> {
+	let mut cases: Vec<PathBuf> = fs::read_dir(dir)
+		.unwrap_or_else(|err| panic!("Can't read {}: {err}", dir.display()))
+		.map(|entry| entry.unwrap().path())
+		.filter(|path| path.extension().is_some_and(|ext| ext == "yd"))
+		.collect();
+
+	cases.sort();
+	cases
+}
+
+/// The `# key: value #` header comments at the start of a case.
+fn header(input: &[u8]) -> Vec<(String, String)> {
+	String::from_utf8_lossy(input)
+		.lines()
+		.map_while(|line| {
+			let line = line.strip_prefix("# ")?.strip_suffix(" #")?;
+			let (key, value) = line.split_once(": ")?;
+			Some((key.to_string(), value.to_string()))
+		})
+		.collect()
+}
+
+/// Whether the cargo features a case asks for (if any) are enabled.
+fn enabled(input: &[u8]) -> bool {
+	header(input)
+		.iter()
+		.filter(|(key, _)| key == "feature")
+		.all(|(_, name)| {
+			let (negated, name) = match name.strip_prefix('!') {
+				Some(name) => (true, name),
+				None => (false, name.as_str()),
+			};
+
+			let enabled = match name {
+				"bytes" => cfg!(feature = "bytes"),
+				"containers" => cfg!(feature = "containers"),
+				other => panic!("Unknown feature {other:?}"),
+			};
+
+			enabled != negated
+		})
+}
+
+/// The parser options a case asks for, if any.
+fn options(input: &[u8]) -> ParserOptions {
+	let mut options = ParserOptions::default();
+
+	for (_, list) in header(input).iter().filter(|(key, _)| key == "options") {
+		for option in list.split(", ") {
+			match option {
+				"numeric_bools" => options.numeric_bools = true,
+				"allow_missing_final_terminator" => options.allow_missing_final_terminator = true,
+				"line_comments" => options.line_comments = true,
+				"strict_idents" => options.strict_idents = true,
+				"lenient_escapes" => options.lenient_escapes = true,
+				"repeated_keys=error" => options.repeated_keys = RepeatedKeys::Error,
+				#[cfg(feature = "containers")]
+				"repeated_keys=collect" => options.repeated_keys = RepeatedKeys::Collect,
+				other => panic!("Unknown option {other:?}"),
+			}
+		}
+	}
+
+	options
+}
+
+fn parse(input: &[u8]) -> yadil::Result<yadil::Message> {
+	Parser::with_options(input, options(input)).parse()
+}
+
+fn expected(case: &Path, extension: &str) -> String {
+	let path = case.with_extension(extension);
+	fs::read_to_string(&path)
+		.unwrap_or_else(|err| panic!("Can't read {}: {err}", path.display()))
+		.trim_end()
+		.to_string()
+}
+
+fn expected_message(path: &Path) -> String {
+	fs::read_to_string(path).unwrap().trim_end().to_string()
+}
+
+/// Renders a map with its keys sorted, so that the output doesn't depend on hashing order.
+fn render(map: &Map, depth: usize, out: &mut String) {
+	let mut entries: Vec<_> = map.iter().collect();
+	entries.sort_by_key(|(key, _)| *key);
+
+	for (key, value) in entries {
+		let indent = "\t".repeat(depth);
+		let key = String::from_utf8_lossy(key);
+
+		match value {
+			#[cfg(feature = "containers")]
+			yadil::Value::Map(inner) => {
+				writeln!(out, "{indent}{key} = {{").unwrap();
+				render(inner, depth + 1, out);
+				writeln!(out, "{indent}}}").unwrap();
+			}
+			other => writeln!(out, "{indent}{key} = {other:?}").unwrap(),
+		}
+	}
+}
+
+#[test]
+fn conformance() {
+	let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/conformance");
+	let mut failures = vec![];
+
+	for case in cases(&root) {
+		let input = fs::read(&case).unwrap();
+
+		if !enabled(&input) {
+			continue;
+		}
+
+		let expected = expected(&case, "debug");
+
+		match parse(&input) {
+			Ok(message) => {
+				let mut actual = String::new();
+				render(&message.0, 0, &mut actual);
+
+				if actual.trim_end() != expected {
+					failures.push(format!(
+						"{}: expected\n{expected}\ngot\n{actual}",
+						case.display()
+					));
+				}
+
+				// Every valid case must also survive writing it back out, compact, pretty and
+				// aligned. Compared rendered, since NaN isn't equal to itself.
+				let written = [
+					yadil::to_bytes(&message),
+					yadil::to_string_pretty(&message, 4).map(String::into_bytes),
+					yadil::to_string_aligned(&message, 4).map(String::into_bytes),
+				];
+
+				for written in written {
+					match written.and_then(|bytes| yadil::parse(&bytes)) {
+						Ok(written) => {
+							let mut rendered = String::new();
+							render(&written.0, 0, &mut rendered);
+
+							if rendered != actual {
+								failures.push(format!(
+									"{}: written back as\n{rendered}",
+									case.display()
+								));
+							}
+						}
+						Err(err) => failures
+							.push(format!("{}: can't write it back: {err:?}", case.display())),
+					}
+				}
+
+				let path = case.with_extension("pretty");
+
+				if path.exists() {
+					match yadil::to_string_pretty(&message, 4) {
+						Ok(pretty) if pretty.trim_end() == expected_message(&path) => {}
+						other => failures.push(format!(
+							"{}: expected pretty\n{}\ngot {other:?}",
+							case.display(),
+							expected_message(&path)
+						)),
+					}
+				}
+			}
+			Err(err) => failures.push(format!("{}: unexpected error {err:?}", case.display())),
+		}
+	}
+
+	for case in cases(&root.join("invalid")) {
+		let input = fs::read(&case).unwrap();
+
+		if !enabled(&input) {
+			continue;
+		}
+
+		let expected = expected(&case, "kind");
+
+		match parse(&input) {
+			Ok(message) => failures.push(format!(
+				"{}: expected {expected}, parsed {:?}",
+				case.display(),
+				message.0
+			)),
+			Err(err) if format!("{:?}", err.kind) != expected => failures.push(format!(
+				"{}: expected {expected}, got {err:?}",
+				case.display()
+			)),
+			Err(err) => {
+				let path = case.with_extension("message");
+
+				if path.exists() && !err.message.contains(&expected_message(&path)) {
+					failures.push(format!(
+						"{}: expected message containing {:?}, got {:?}",
+						case.display(),
+						expected_message(&path),
+						err.message
+					));
+				}
+
+				let path = case.with_extension("span");
+
+				if path.exists() && format!("{:?}", err.span()) != expected_message(&path) {
+					failures.push(format!(
+						"{}: expected span {}, got {:?}",
+						case.display(),
+						expected_message(&path),
+						err.span()
+					));
+				}
+
+				let path = case.with_extension("position");
+				let position = format!("{}:{}", err.line, err.col);
+
+				if path.exists() && position != expected_message(&path) {
+					failures.push(format!(
+						"{}: expected position {}, got {position}",
+						case.display(),
+						expected_message(&path),
+					));
+				}
+			}
+		}
+	}
+
+	assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
@@ -0,0 +1,46 @@
+//! The data types the parser knows.
+
+use yadil::{ErrorKind, SUPPORTED_TYPES};
+
+/// A valid assignment of `data_type`.
+fn assignment(data_type: &str) -> String {
+	let value = match data_type {
+		"s" | "str" => "=text;",
+		"u" | "uint" | "y" | "byte" => "=1;",
+		"i" | "sint" => "=-1;",
+		"f" | "float" => "=1.5;",
+		"b" | "bool" => "=true;",
+		"x" | "bytes" => "=[1, 0x02];",
+		"r" | "raw" => "=anything;",
+		// Lists need the type of their elements.
+		"l" | "list" => return format!("{data_type}.u@k=[1];"),
+		"m" | "map" => ":e;",
+		other => panic!("no sample value for `{other}`"),
+	};
+
+	format!("{data_type}@k{value}")
+}
+
+#[test]
+fn every_supported_type_parses() {
+	for data_type in SUPPORTED_TYPES {
+		let input = assignment(data_type);
+		let message = yadil::parse(input.as_bytes()).unwrap_or_else(|err| panic!("{input}: {err}"));
+		assert!(message.get("k").is_some(), "{input}");
+	}
+}
+
+#[test]
+fn built_in_types_cannot_be_aliases() {
+	// Types of disabled features are still taken.
+	let names = SUPPORTED_TYPES
+		.iter()
+		.chain(&["y", "bytes", "map", "l.u", "list.str"]);
+
+	for name in names {
+		let input = format!("@alias {name} = uint;");
+		let err = yadil::parse(input.as_bytes()).unwrap_err();
+		assert_eq!(err.kind, ErrorKind::UnexpectedChar, "{input}");
+		assert!(err.message.contains("built-in data type"), "{input}: {err}");
+	}
+}
@@ -0,0 +1,25 @@
+//! The error type, as seen by callers.
+
+use yadil::{Error, ErrorKind};
+
+#[test]
+fn recovers_boxed_errors() {
+	let boxed: Box<dyn std::error::Error> = Box::new(yadil::parse(b"u@a=x;").unwrap_err());
+	let err = Error::from_boxed(boxed.as_ref()).unwrap();
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+
+	let other: Box<dyn std::error::Error> = "not yadil".into();
+	assert!(Error::from_boxed(other.as_ref()).is_none());
+}
+
+#[test]
+fn displays_the_position() {
+	let err = yadil::parse(b"u@a=1;\nu@b=x;").unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		format!("WrongValue at 2:5 (index 11): {}", err.message)
+	);
+
+	let err = Error::new(ErrorKind::Io, "closed".to_string(), 3);
+	assert_eq!(err.to_string(), "Io at index 3: closed");
+}
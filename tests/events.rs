@@ -0,0 +1,59 @@
+//! Event-based parsing of nested messages.
+
+#![cfg(feature = "containers")]
+
+use yadil::{ErrorKind, Event, Parser, Value};
+
+fn events(input: &[u8]) -> yadil::Result<Vec<Event>> {
+	let mut parser = Parser::new(input);
+	let mut events = vec![];
+
+	while let Some(event) = parser.next_event()? {
+		events.push(event);
+	}
+
+	Ok(events)
+}
+
+fn key(key: &str) -> Event {
+	Event::KeyStart(key.as_bytes().to_vec())
+}
+
+#[test]
+fn nested_document() {
+	let input = b"s@name=demo; # comment # m@server: u@port=80; m@tls: b@on=t; e; l.l.u@grid=[[1], []]; e; i@last=-1;";
+
+	assert_eq!(
+		events(input).unwrap(),
+		[
+			key("name"),
+			Event::Scalar(Value::String("demo".into())),
+			key("server"),
+			Event::MapStart,
+			key("port"),
+			Event::Scalar(Value::Unsigned(80)),
+			key("tls"),
+			Event::MapStart,
+			key("on"),
+			Event::Scalar(Value::Bool(true)),
+			Event::MapEnd,
+			key("grid"),
+			Event::ListStart,
+			Event::ListStart,
+			Event::Scalar(Value::Unsigned(1)),
+			Event::ListEnd,
+			Event::ListStart,
+			Event::ListEnd,
+			Event::ListEnd,
+			Event::MapEnd,
+			key("last"),
+			Event::Scalar(Value::Signed(-1)),
+		]
+	);
+}
+
+#[test]
+fn unterminated_map() {
+	let err = events(b"m@server: u@port=80;").unwrap_err();
+	assert_eq!(err.kind, ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,44 @@
+//! Buffers holding several null-terminated messages.
+
+use yadil::{ErrorKind, Value};
+
+#[test]
+fn parses_every_frame() {
+	let messages = yadil::parse_framed(b"u@a=1;\0s@b=two;\0").unwrap();
+
+	assert_eq!(messages.len(), 2);
+	assert_eq!(messages[0].get("a"), Some(&Value::Unsigned(1)));
+	assert_eq!(messages[1].get("b"), Some(&Value::from("two")));
+	assert_eq!(yadil::parse_framed(b"u@a=1;\0s@b=two;").unwrap(), messages);
+}
+
+#[test]
+fn reports_errors_against_the_whole_buffer() {
+	let err = yadil::parse_framed(b"u@a=1;\0\nu@b=x;").unwrap_err();
+	let in_frame = yadil::parse(b"\nu@b=x;").unwrap_err();
+
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+	assert_eq!(err.index, in_frame.index + 7);
+	assert_eq!((err.line, err.col), (in_frame.line, in_frame.col));
+}
+
+#[test]
+fn lenient_stream_goes_on_after_an_invalid_frame() {
+	let input = b"u@a=1;\0\nu@b=x;\0s@c=three;\0";
+	let results: Vec<_> = yadil::parse_stream_lenient(input).collect();
+
+	assert_eq!(results.len(), 3);
+	assert_eq!(
+		results[0].as_ref().unwrap().get("a"),
+		Some(&Value::Unsigned(1))
+	);
+	assert_eq!(
+		results[2].as_ref().unwrap().get("c"),
+		Some(&Value::from("three"))
+	);
+
+	// Errors are placed like with `parse_framed`.
+	let err = results[1].as_ref().unwrap_err();
+	assert_eq!(err, &yadil::parse_framed(input).unwrap_err());
+	assert_eq!((err.index, err.line), (12, 2));
+}
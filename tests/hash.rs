@@ -0,0 +1,61 @@
+//! Content hashes of messages.
+
+use yadil::Message;
+
+fn hex(message: &Message) -> String {
+	message
+		.content_hash()
+		.iter()
+		.map(|byte| format!("{byte:02x}"))
+		.collect()
+}
+
+#[test]
+fn equal_messages_hash_the_same() {
+	let message = yadil::parse(b"u@a=1;s@b=two;f@c=0.0;").unwrap();
+	let reordered = yadil::parse(b"f@c=-0.0;s@b=two;u@a=1;").unwrap();
+	let different = yadil::parse(b"u@a=2;s@b=two;f@c=0.0;").unwrap();
+
+	assert_eq!(message.content_hash(), reordered.content_hash());
+	assert_ne!(message.content_hash(), different.content_hash());
+}
+
+#[test]
+fn empty_message_hashes_as_empty_input() {
+	// The SHA-256 of no bytes, since an empty message has no entries to encode.
+	assert_eq!(
+		hex(&yadil::parse(b"").unwrap()),
+		"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+	);
+}
+
+#[test]
+fn hashes_across_block_boundaries() {
+	// Setting `k` to `len` `a`s gives an encoding of `18 + len` bytes. These hit the longest
+	// input whose padding fits in one block (55 bytes), the shortest that spills into a second
+	// one (56), a whole block (64), and over a million bytes. The hashes were computed
+	// independently.
+	let cases = [
+		(
+			37,
+			"92028c1d41a44985a10e72a05646145cd5e96636062219fbdf67dcdaa4faa146",
+		),
+		(
+			38,
+			"973683aad9e3b7b1e1fe98a700d990d7ae415f829bc00027a0fbd34afb854a27",
+		),
+		(
+			46,
+			"c86eb1fd8b20422429d7e24c257ccb5f29d8985f7dd3d9c8aac34e69ddfe4aaf",
+		),
+		(
+			1_000_000,
+			"eae94d0b3f592346aeafbbb3db9c56357e30758575696e4f58ef702ead4632f1",
+		),
+	];
+
+	for (len, expected) in cases {
+		let message: Message = [("k", "a".repeat(len))].into_iter().collect();
+		assert_eq!(hex(&message), expected, "string of {len} bytes");
+	}
+}
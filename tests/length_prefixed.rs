@@ -0,0 +1,40 @@
+//! Messages read from a stream behind a length prefix.
+
+use std::io::Cursor;
+
+use yadil::{ErrorKind, Value};
+
+fn frame(prefix: u32, body: &[u8]) -> Cursor<Vec<u8>> {
+	let mut bytes = prefix.to_be_bytes().to_vec();
+	bytes.extend_from_slice(body);
+	Cursor::new(bytes)
+}
+
+#[test]
+fn reads_a_frame() {
+	let mut stream = frame(6, b"u@a=1;u@b=2;");
+	let message = yadil::parse_length_prefixed(&mut stream).unwrap();
+
+	assert_eq!(message.get("a"), Some(&Value::Unsigned(1)));
+	assert_eq!(message.get("b"), None);
+	assert_eq!(stream.position(), 10);
+}
+
+#[test]
+fn short_prefix_fails() {
+	let err = yadil::parse_length_prefixed(&mut Cursor::new(vec![0, 0])).unwrap_err();
+
+	assert_eq!(err.kind, ErrorKind::UnexpectedEof);
+	assert!(err.message.starts_with("Truncated length prefix"));
+	assert_eq!(err.index, 2);
+}
+
+#[test]
+fn short_frame_fails() {
+	// The declared 4 GiB mustn't be allocated up front.
+	let err = yadil::parse_length_prefixed(&mut frame(u32::MAX, b"u@a=1;")).unwrap_err();
+
+	assert_eq!(err.kind, ErrorKind::UnexpectedEof);
+	assert!(err.message.starts_with("Truncated frame"));
+	assert_eq!(err.index, 10);
+}
@@ -0,0 +1,57 @@
+//! Lists written one element at a time.
+
+#![cfg(feature = "containers")]
+
+use yadil::{ErrorKind, ListWriter, Value};
+
+#[test]
+fn streams_a_large_list() {
+	let mut writer = ListWriter::new(vec![], b"ids", "uint").unwrap();
+
+	for i in 0..100_000 {
+		writer.push(&Value::Unsigned(i)).unwrap();
+	}
+
+	let out = writer.finish().unwrap();
+	assert!(out.starts_with(b"l.u@ids=[0,1,2,"));
+
+	let message = yadil::parse(&out).unwrap();
+	let Some(Value::List(list)) = message.get("ids") else {
+		panic!("expected a list");
+	};
+	assert_eq!(list.len(), 100_000);
+	assert!(list
+		.iter()
+		.zip(0..)
+		.all(|(value, i)| *value == Value::Unsigned(i)));
+}
+
+#[test]
+fn escapes_elements_and_closes_on_drop() {
+	let mut out = vec![];
+	let mut writer = ListWriter::new(&mut out, b"my key", "s").unwrap();
+	writer.push(&Value::from("a, b")).unwrap();
+	writer.push(&Value::from(" ]; ")).unwrap();
+
+	let err = writer.push(&Value::Unsigned(1)).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+	assert_eq!(
+		writer.push(&Value::from("")).unwrap_err().kind,
+		ErrorKind::WrongValue
+	);
+	drop(writer);
+
+	let message = yadil::parse(&out).unwrap();
+	assert_eq!(
+		message.get("my key"),
+		Some(&Value::List(vec![Value::from("a, b"), Value::from(" ]; ")]))
+	);
+
+	let empty = ListWriter::new(vec![], b"empty", "f")
+		.unwrap()
+		.finish()
+		.unwrap();
+	assert_eq!(empty, b"l.f@empty=[];");
+	assert!(ListWriter::new(vec![], b"k", "map").is_err());
+	assert!(ListWriter::new(vec![], b"", "u").is_err());
+}
@@ -0,0 +1,200 @@
+//! Accessors of parsed messages.
+
+use yadil::{Message, Shape, Value};
+
+#[test]
+fn iterates_over_entries() {
+	let message = yadil::parse(b"u@a=1;s@b\\ c=x;s@\xff=y;").unwrap();
+
+	let mut keys: Vec<&[u8]> = message.iter().map(|(key, _)| key).collect();
+	keys.sort_unstable();
+	assert_eq!(keys, [&b"a"[..], b"b c", b"\xff"]);
+
+	let mut borrowed = 0;
+	for (key, value) in &message {
+		assert_eq!(message.get(key), Some(value));
+		borrowed += 1;
+	}
+	assert_eq!(borrowed, 3);
+
+	let mut owned: Vec<(Vec<u8>, Value)> = message.into_iter().collect();
+	owned.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+	assert_eq!(owned[0], (b"a".to_vec(), Value::Unsigned(1)));
+}
+
+#[test]
+fn builds_a_message() {
+	let mut message = Message::new();
+	assert_eq!(message.insert("name", "yadil"), None);
+	assert_eq!(message.insert(b"port".to_vec(), 80usize), None);
+	assert_eq!(message.insert("port", 8080usize), Some(Value::Unsigned(80)));
+
+	let written = yadil::to_bytes(&message).unwrap();
+	assert_eq!(yadil::parse(&written).unwrap(), message);
+
+	let collected: Message = [
+		("name", Value::from("yadil")),
+		("port", Value::from(8080usize)),
+	]
+	.into_iter()
+	.collect();
+	assert_eq!(collected, message);
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn pushes_to_lists() {
+	let mut message = yadil::parse(b"l.u@list=[1];u@number=2;").unwrap();
+
+	message.push_to_list("list", Value::from(2usize)).unwrap();
+	message.push_to_list("new", Value::from("first")).unwrap();
+	assert_eq!(
+		message.get("list"),
+		Some(&Value::List(vec![Value::Unsigned(1), Value::Unsigned(2)]))
+	);
+	assert_eq!(
+		message.get("new"),
+		Some(&Value::List(vec![Value::from("first")]))
+	);
+
+	let err = message
+		.push_to_list("number", Value::from(3usize))
+		.unwrap_err();
+	assert_eq!(err.kind, yadil::ErrorKind::WrongValue);
+	assert_eq!(message.get("number"), Some(&Value::Unsigned(2)));
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn reads_and_writes_paths() {
+	let mut message = yadil::parse(b"m@server:u@port=80;e;s@name=demo;").unwrap();
+	assert_eq!(message.get_path("server.port"), Some(&Value::Unsigned(80)));
+	assert_eq!(message.get_path("server.host"), None);
+	assert_eq!(message.get_path("name.first"), None);
+
+	message
+		.set_path("server.port", Value::from(8080usize))
+		.unwrap();
+	message
+		.set_path("server.tls.on", Value::Bool(true))
+		.unwrap();
+	assert_eq!(
+		message.get_path("server.port"),
+		Some(&Value::Unsigned(8080))
+	);
+	assert_eq!(message.get_path("server.tls.on"), Some(&Value::Bool(true)));
+
+	let err = message
+		.set_path("name.first", Value::from("x"))
+		.unwrap_err();
+	assert_eq!(err.kind, yadil::ErrorKind::WrongValue);
+	assert_eq!(message.get("name"), Some(&Value::from("demo")));
+}
+
+#[test]
+fn gets_trimmed_keys() {
+	let message = yadil::parse(b"s@\\ padded\\ =x;s@plain=y;").unwrap();
+
+	assert_eq!(message.get("padded"), None);
+	assert_eq!(message.get_trimmed("padded"), Some(&Value::from("x")));
+	assert_eq!(message.get_trimmed(" plain\t"), Some(&Value::from("y")));
+	assert_eq!(message.get_trimmed("missing"), None);
+}
+
+#[test]
+fn edits_values_in_place() {
+	let mut message = yadil::parse(b"u@count=1;").unwrap();
+
+	if let Some(Value::Unsigned(count)) = message.get_mut("count") {
+		*count += 1;
+	}
+	assert_eq!(message.get("count"), Some(&Value::Unsigned(2)));
+	assert_eq!(message.get_mut("missing"), None);
+}
+
+#[test]
+fn documents_are_maps() {
+	assert_eq!(Message::new().shape(), Shape::Map);
+	assert_eq!(yadil::parse(b"u@a=1;").unwrap().shape(), Shape::Map);
+}
+
+#[test]
+fn iterates_in_key_order() {
+	let message = yadil::parse(b"u@b=2;u@\xff=3;u@a=1;u@B=0;").unwrap();
+	let keys: Vec<&[u8]> = message.sorted_iter().map(|(key, _)| &key[..]).collect();
+
+	assert_eq!(keys, [&b"B"[..], b"a", b"b", b"\xff"]);
+	assert_eq!(message.sorted_iter().count(), message.iter().count());
+}
+
+#[test]
+fn compares_keys() {
+	let old = yadil::parse(b"u@kept=1;u@removed=2;u@gone=3;").unwrap();
+	let new = yadil::parse(b"u@kept=4;u@added=5;").unwrap();
+
+	assert_eq!(
+		old.difference_keys(&new),
+		(
+			vec![b"gone".to_vec(), b"removed".to_vec()],
+			vec![b"added".to_vec()]
+		)
+	);
+	assert_eq!(old.difference_keys(&old), (vec![], vec![]));
+}
+
+#[test]
+fn finds_keys_that_are_not_utf8() {
+	assert_eq!(
+		yadil::parse(b"u@a=1;").unwrap().validate_utf8_keys(),
+		Ok(())
+	);
+
+	let message = yadil::parse(b"u@\xff=1;u@ok=2;").unwrap();
+	assert_eq!(message.validate_utf8_keys(), Err(vec![b"\xff".to_vec()]));
+
+	#[cfg(feature = "containers")]
+	{
+		let nested = yadil::parse(b"m@outer:u@ok=1;m@inner:u@\xfe=1;e;e;").unwrap();
+		assert_eq!(nested.validate_utf8_keys(), Err(vec![b"\xfe".to_vec()]));
+	}
+}
+
+#[test]
+fn merges_layers_from_bytes() {
+	let mut message = yadil::parse(b"u@port=80;s@host=localhost;").unwrap();
+
+	message.merge_from_bytes(b"u@port=8080;b@debug=t;").unwrap();
+	assert_eq!(message.get("port"), Some(&Value::Unsigned(8080)));
+	assert_eq!(message.get("host"), Some(&Value::from("localhost")));
+	assert_eq!(message.get("debug"), Some(&Value::Bool(true)));
+
+	// Invalid input leaves the message untouched.
+	let merged = message.clone();
+	assert!(message.merge_from_bytes(b"u@port=1;u@bad=x;").is_err());
+	assert_eq!(message, merged);
+
+	#[cfg(feature = "containers")]
+	{
+		let mut message = yadil::parse(b"m@server:u@port=80;s@host=a;e;").unwrap();
+		message
+			.merge_from_bytes(b"m@server:u@port=8080;e;")
+			.unwrap();
+		assert_eq!(
+			message.get_path("server.port"),
+			Some(&Value::Unsigned(8080))
+		);
+		assert_eq!(message.get_path("server.host"), Some(&Value::from("a")));
+	}
+}
+
+#[test]
+fn gets_values_by_key() {
+	let message = yadil::parse(b"s@name=yadil;u@\xff=1;").unwrap();
+
+	assert_eq!(message.get("name"), Some(&Value::from("yadil")));
+	assert_eq!(message.get(b"name"), Some(&Value::from("yadil")));
+	assert_eq!(message.get(&b"\xff"[..]), Some(&Value::Unsigned(1)));
+	assert_eq!(message.get_str("name"), Some(&Value::from("yadil")));
+	assert_eq!(message.get("missing"), None);
+	assert_eq!(message.get_str("missing"), None);
+}
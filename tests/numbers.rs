@@ -0,0 +1,71 @@
+//! Numeric values, and where the errors in them point.
+
+use std::ops::Range;
+
+use yadil::{ErrorKind, Value};
+
+/// Parses `input` and returns the value of `k`, or the span of the error. Values start at index 4.
+fn scan(input: &str) -> Result<Value, Range<usize>> {
+	match yadil::parse(input.as_bytes()) {
+		Ok(message) => Ok(message.get("k").unwrap().clone()),
+		Err(err) => {
+			assert_eq!(err.kind, ErrorKind::WrongValue, "{input}: {}", err.message);
+			Err(err.span())
+		}
+	}
+}
+
+#[test]
+fn radix_prefixes() {
+	assert_eq!(scan("u@k=0xfF;"), Ok(Value::Unsigned(255)));
+	assert_eq!(scan("u@k=0o17;"), Ok(Value::Unsigned(15)));
+	assert_eq!(scan("u@k=0b101;"), Ok(Value::Unsigned(5)));
+
+	// A prefix without digits covers the whole value.
+	assert_eq!(scan("u@k=0x;"), Err(4..6));
+}
+
+#[test]
+fn digits_invalid_for_the_radix() {
+	assert_eq!(scan("u@k=0xfg;"), Err(7..8));
+	assert_eq!(scan("u@k=0o8;"), Err(6..7));
+	assert_eq!(scan("u@k=0b102;"), Err(8..9));
+	assert_eq!(scan("u@k=12a;"), Err(6..7));
+}
+
+#[test]
+fn separators() {
+	assert_eq!(scan("u@k=1_000;"), Ok(Value::Unsigned(1000)));
+	assert_eq!(scan("i@k=-1_0;"), Ok(Value::Signed(-10)));
+	assert_eq!(scan("f@k=1_0.2_5;"), Ok(Value::Float(10.25)));
+
+	// Errors point at the first misplaced `_`.
+	assert_eq!(scan("u@k=_1;"), Err(4..5));
+	assert_eq!(scan("u@k=1_;"), Err(5..6));
+	assert_eq!(scan("u@k=1__0;"), Err(5..6));
+	assert_eq!(scan("i@k=-_1;"), Err(5..6));
+	assert_eq!(scan("f@k=1._5;"), Err(6..7));
+	assert_eq!(scan("f@k=1e_2;"), Err(6..7));
+}
+
+#[test]
+fn exponents() {
+	assert_eq!(scan("f@k=1.5e3;"), Ok(Value::Float(1500.0)));
+	assert_eq!(scan("f@k=2E-2;"), Ok(Value::Float(0.02)));
+	assert_eq!(scan("f@k=1e+1_0;"), Ok(Value::Float(1e10)));
+
+	// Without digits, errors point at the `e`.
+	assert_eq!(scan("f@k=1e;"), Err(5..6));
+	assert_eq!(scan("f@k=1e-;"), Err(5..6));
+	assert_eq!(scan("f@k=1ex;"), Err(6..7));
+}
+
+#[test]
+fn misplaced_signs_and_points() {
+	assert_eq!(scan("i@k=1-2;"), Err(5..6));
+	assert_eq!(scan("f@k=1.2.3;"), Err(7..8));
+
+	// A value without digits is covered as a whole.
+	assert_eq!(scan("i@k=-;"), Err(4..5));
+	assert_eq!(scan("f@k=.;"), Err(4..5));
+}
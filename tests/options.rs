@@ -0,0 +1,150 @@
+//! Parsers configured with non-default options.
+
+use yadil::{ErrorKind, Parser, ParserOptions, RepeatedKeys, Value};
+
+#[test]
+fn options_take_effect() {
+	let input = b"b@on=1;b@on=0;";
+	let options = ParserOptions::default().numeric_bools(true);
+	let message = Parser::with_options(input, options).parse().unwrap();
+
+	assert_eq!(message.0[&b"on"[..]], Value::Bool(false));
+
+	let options = options.repeated_keys(RepeatedKeys::Error);
+	let err = Parser::with_options(input, options).parse().unwrap_err();
+
+	assert_eq!(err.kind, ErrorKind::DuplicateKey);
+	assert!(Parser::new(input).parse().is_err());
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn nesting_past_max_depth_fails() {
+	let lists = format!(
+		"{}u@k={}{};",
+		"l.".repeat(129),
+		"[".repeat(129),
+		"]".repeat(129)
+	);
+	let maps = format!("{}{}", "m@k:".repeat(129), "e;".repeat(129));
+
+	for input in [lists, maps] {
+		let err = yadil::parse(input.as_bytes()).unwrap_err();
+		assert_eq!(err.kind, ErrorKind::DepthLimitExceeded);
+
+		let options = ParserOptions::default().max_depth(129);
+		assert!(Parser::with_options(input.as_bytes(), options)
+			.parse()
+			.is_ok());
+	}
+
+	let options = ParserOptions::default().max_depth(2);
+	let err = Parser::with_options(b"m@a:l.l.u@b=[[1]];e;", options)
+		.parse()
+		.unwrap_err();
+
+	assert_eq!(err.kind, ErrorKind::DepthLimitExceeded);
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn repeated_keys_in_maps() {
+	let parse = |input: &[u8], repeated_keys| {
+		let options = ParserOptions::default().repeated_keys(repeated_keys);
+		Parser::with_options(input, options).parse()
+	};
+	let input = b"m@s:u@a=1;u@a=2;e;";
+	let inner = |message: yadil::Message| message.0[&b"s"[..]].as_map().unwrap()[&b"a"[..]].clone();
+
+	assert_eq!(
+		inner(parse(input, RepeatedKeys::LastWins).unwrap()),
+		Value::Unsigned(2)
+	);
+	assert_eq!(
+		inner(parse(input, RepeatedKeys::Collect).unwrap()),
+		Value::List(vec![Value::Unsigned(1), Value::Unsigned(2)])
+	);
+
+	for input in [&input[..], b"m@s:m@a:e;m@a:e;e;"] {
+		let err = parse(input, RepeatedKeys::Error).unwrap_err();
+		assert_eq!(err.kind, ErrorKind::DuplicateKey);
+		assert_eq!(err.span(), 10..16);
+	}
+}
+
+#[test]
+fn numeric_bools() {
+	let options = ParserOptions::default().numeric_bools(true);
+	let parse = |input: &[u8]| Parser::with_options(input, options).parse();
+
+	let message = parse(b"b@x=0;b@y=1;b@z=0.0;b@w=01.00;").unwrap();
+	assert_eq!(message.get("x"), Some(&Value::Bool(false)));
+	assert_eq!(message.get("y"), Some(&Value::Bool(true)));
+	assert_eq!(message.get("z"), Some(&Value::Bool(false)));
+	assert_eq!(message.get("w"), Some(&Value::Bool(true)));
+
+	for input in [&b"b@x=2;"[..], b"b@x=0.5;", b"b@x=-1;"] {
+		assert_eq!(parse(input).unwrap_err().kind, ErrorKind::WrongValue);
+	}
+	assert!(Parser::new(b"b@x=0;").parse().is_err());
+}
+
+#[test]
+fn lenient_escapes() {
+	let input = b"s@known=a\\nb;s@unknown=a\\qb;";
+	let err = Parser::new(input).parse().unwrap_err();
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+	assert_eq!(err.message, "Unknown escape `\\q`");
+
+	let options = ParserOptions::default().lenient_escapes(true);
+	let message = Parser::with_options(input, options).parse().unwrap();
+	assert_eq!(message.get("known"), Some(&Value::from("a\nb")));
+	assert_eq!(message.get("unknown"), Some(&Value::from("a\\qb")));
+
+	#[cfg(feature = "containers")]
+	{
+		let message = Parser::with_options(b"l.s@list=[\\q, \\,];", options)
+			.parse()
+			.unwrap();
+		assert_eq!(
+			message.get("list"),
+			Some(&Value::List(vec![Value::from("\\q"), Value::from(",")]))
+		);
+	}
+}
+
+#[test]
+fn captured_spans_give_raw_assignments() {
+	let input = b"# header #\nu @ port\t= 80;\n  s@name = a\\ b;#trailing#s@name=last;";
+	let options = ParserOptions::default().capture_spans(true);
+	let mut parser = Parser::with_options(input, options);
+	parser.parse().unwrap();
+
+	assert_eq!(parser.raw("port"), Some(&b"u @ port\t= 80;"[..]));
+	// The last assignment of a repeated key, like the value the message keeps.
+	assert_eq!(parser.raw("name"), Some(&b"s@name=last;"[..]));
+	assert_eq!(parser.spans[1], (b"name".to_vec(), 28..42));
+	assert_eq!(parser.raw("missing"), None);
+
+	assert!(Parser::new(input).spans.is_empty());
+	let mut parser = Parser::new(input);
+	parser.parse().unwrap();
+	assert_eq!(parser.raw("port"), None);
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn captured_spans_cover_whole_maps() {
+	let input = b"m@server: # note # u@port=80;\n\ts@host = local ; end ;u@n=1;";
+	let options = ParserOptions::default().capture_spans(true);
+	let mut parser = Parser::with_options(input, options);
+	parser.parse().unwrap();
+
+	assert_eq!(
+		parser.raw("server"),
+		Some(&b"m@server: # note # u@port=80;\n\ts@host = local ; end ;"[..])
+	);
+	// Only top-level assignments are recorded.
+	assert_eq!(parser.spans.len(), 2);
+	assert_eq!(parser.raw("port"), None);
+}
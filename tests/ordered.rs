@@ -0,0 +1,25 @@
+//! Messages keeping their entries in the order they were written.
+
+#![cfg(feature = "ordered")]
+
+#[test]
+fn entries_keep_source_order() {
+	let message = yadil::parse(b"s@zebra=1;s@apple=2;s@mango=3;").unwrap();
+	let keys: Vec<_> = message.0.keys().map(|key| key.as_slice()).collect();
+
+	assert_eq!(keys, [&b"zebra"[..], b"apple", b"mango"]);
+	assert_eq!(
+		yadil::to_bytes(&message).unwrap(),
+		b"s@zebra=1;s@apple=2;s@mango=3;"
+	);
+}
+
+#[cfg(all(feature = "bytes", feature = "containers"))]
+#[test]
+fn flattens_map_bytes_in_source_order() {
+	let message = yadil::parse(b"m@m:y@z=1;x@a=[2, 3];s@s=x;y@m=4;e;").unwrap();
+	let value = message.get("m").unwrap();
+
+	assert_eq!(value.flatten_bytes(), [1, 2, 3, 4]);
+	assert!(value.try_flatten_bytes().is_err());
+}
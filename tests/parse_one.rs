@@ -0,0 +1,45 @@
+//! Parsing one top-level assignment at a time.
+
+use yadil::{Assign, ErrorKind, Parser, Value};
+
+#[test]
+fn parses_concatenated_assignments() {
+	let mut parser = Parser::new(b"u@a=1; s@b=two;  ");
+
+	let first = parser.parse_one().unwrap();
+	assert_eq!(first, Some(Assign(b"a".to_vec(), Value::Unsigned(1))));
+	assert_eq!(parser.remaining(), b" s@b=two;  ");
+
+	let second = parser.parse_one().unwrap();
+	assert_eq!(second, Some(Assign(b"b".to_vec(), Value::from("two"))));
+	assert_eq!(parser.remaining(), b"  ");
+
+	assert_eq!(parser.parse_one().unwrap(), None);
+	assert!(parser.at_end());
+}
+
+#[test]
+fn iterates_over_assignments() {
+	let mut parser = Parser::new(b"u@a=1; s@b=two; u@c=x; u@d=4;");
+	let mut exprs = parser.exprs();
+
+	assert_eq!(
+		exprs.next().unwrap().unwrap(),
+		Assign(b"a".to_vec(), Value::Unsigned(1))
+	);
+	assert_eq!(
+		exprs.next().unwrap().unwrap(),
+		Assign(b"b".to_vec(), Value::from("two"))
+	);
+
+	// Iteration ends after the first error.
+	let err = exprs.next().unwrap().unwrap_err();
+	assert_eq!((err.kind, err.index), (ErrorKind::WrongValue, 20));
+	assert!(exprs.next().is_none());
+
+	let keys: Vec<_> = Parser::new(b"u@a=1;u@b=2;")
+		.exprs()
+		.map(|assign| assign.unwrap().0)
+		.collect();
+	assert_eq!(keys, [b"a", b"b"]);
+}
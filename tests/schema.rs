@@ -0,0 +1,28 @@
+//! Messages parsed against a schema.
+
+use yadil::{Schema, Value};
+
+fn schema() -> Schema {
+	Schema::new()
+		.optional("port", Value::Unsigned(80))
+		.optional("host", Value::from("localhost"))
+}
+
+#[test]
+fn fills_in_missing_keys() {
+	let message = yadil::parse_with_schema(b"u@port=8080;", &schema()).unwrap();
+
+	assert_eq!(message.get("port"), Some(&Value::Unsigned(8080)));
+	assert_eq!(message.get("host"), Some(&Value::from("localhost")));
+	assert_eq!(message.iter().count(), 2);
+}
+
+#[test]
+fn leaves_set_keys_alone() {
+	let mut message = yadil::parse(b"s@host=example.org;u@port=1;b@debug=t;").unwrap();
+	let parsed = message.clone();
+
+	schema().apply_defaults(&mut message);
+	assert_eq!(message, parsed);
+	assert!(yadil::parse_with_schema(b"u@port=x;", &schema()).is_err());
+}
@@ -0,0 +1,60 @@
+//! Serialization of parsed messages and deserialization of values with serde.
+
+#![cfg(all(feature = "serde", feature = "containers"))]
+
+use serde_json::json;
+use yadil::{Map, Value};
+
+#[test]
+fn nested_message_to_json() {
+	let message = yadil::parse(
+		b"s@name=demo;i@offset=-3;f@ratio=0.5;b@on=t;l.u@ids=[1,2];m@server:s@host=localhost;u@port=8080;e;",
+	)
+	.unwrap();
+
+	assert_eq!(
+		serde_json::to_value(&message).unwrap(),
+		json!({
+			"ids": [1, 2],
+			"name": "demo",
+			"offset": -3,
+			"on": true,
+			"ratio": 0.5,
+			"server": { "host": "localhost", "port": 8080 },
+		})
+	);
+}
+
+#[test]
+fn json_object_to_value() {
+	let value: Value = serde_json::from_str(
+		r#"{"name": "demo", "offset": -3, "ratio": 0.5, "ids": [1, 2], "on": true}"#,
+	)
+	.unwrap();
+
+	let expected = Map::from([
+		(b"name".to_vec(), Value::String("demo".into())),
+		(b"offset".to_vec(), Value::Signed(-3)),
+		(b"ratio".to_vec(), Value::Float(0.5)),
+		(
+			b"ids".to_vec(),
+			Value::List(vec![Value::Unsigned(1), Value::Unsigned(2)]),
+		),
+		(b"on".to_vec(), Value::Bool(true)),
+	]);
+
+	assert_eq!(value, Value::Map(expected));
+}
+
+#[test]
+fn escapes_keys_that_arent_utf8() {
+	let mut map = Map::new();
+	map.insert(b"\xff".to_vec(), Value::Unsigned(1));
+	map.insert(b"a\\\xfe\xffb".to_vec(), Value::Unsigned(2));
+	map.insert(b"ok".to_vec(), Value::Unsigned(3));
+
+	assert_eq!(
+		serde_json::to_value(Value::Map(map)).unwrap(),
+		json!({ "\\xff": 1, "a\\\\\\xfe\\xffb": 2, "ok": 3 })
+	);
+}
@@ -0,0 +1,244 @@
+//! Conversions between values and Rust types.
+
+use yadil::{ErrorKind, Number, Value};
+
+#[test]
+fn try_from_matching_variant() {
+	let message =
+		yadil::parse(b"s@name=yadil;u@port=80;i@offset=-2;f@ratio=0.5;b@on=true;").unwrap();
+
+	let name: String = message.get("name").unwrap().try_into().unwrap();
+	let port: usize = message.get("port").unwrap().try_into().unwrap();
+	let offset = isize::try_from(message.get("offset").unwrap()).unwrap();
+	let ratio = f64::try_from(message.get("ratio").unwrap().clone()).unwrap();
+	let on = bool::try_from(message.get("on").unwrap().clone()).unwrap();
+
+	assert_eq!(
+		(name.as_str(), port, offset, ratio, on),
+		("yadil", 80, -2, 0.5, true)
+	);
+	assert_eq!(
+		String::try_from(Value::String("owned".into())).unwrap(),
+		"owned"
+	);
+}
+
+#[test]
+fn try_from_other_variant() {
+	let err = usize::try_from(Value::Signed(-1)).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+
+	let err = String::try_from(&Value::Bool(true)).unwrap_err();
+	assert_eq!(err.kind, ErrorKind::WrongValue);
+	assert_eq!(err.message, "Expected a string value, found Bool(true)");
+}
+
+#[test]
+fn from_primitives() {
+	assert_eq!(Value::from("yadil"), Value::String("yadil".into()));
+	assert_eq!(
+		Value::from(String::from("yadil")),
+		Value::String("yadil".into())
+	);
+	assert_eq!(Value::from(80usize), Value::Unsigned(80));
+	assert_eq!(Value::from(-2isize), Value::Signed(-2));
+	assert_eq!(Value::from(0.5), Value::Float(0.5));
+	assert_eq!(Value::from(true), Value::Bool(true));
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn from_list() {
+	let elements: Vec<Value> = vec![1usize.into(), "two".into()];
+	let list = Value::from(elements);
+
+	assert_eq!(
+		list,
+		Value::List(vec![Value::Unsigned(1), Value::String("two".into())])
+	);
+}
+
+#[test]
+fn displays_as_yadil() {
+	assert_eq!(Value::from("a;b").to_string(), r"a\;b");
+	assert_eq!(Value::from(-2isize).to_string(), "-2");
+
+	// Empty strings can't be written in YADIL, but displaying them doesn't fail.
+	assert_eq!(
+		Value::from("").to_string(),
+		"<no YADIL text: Empty strings can't be written>"
+	);
+	assert_eq!(
+		format!("name: {}", Value::from("")),
+		"name: <no YADIL text: Empty strings can't be written>"
+	);
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn displays_containers_as_yadil() {
+	let message = yadil::parse(b"l.l.u@list=[[1,2],[]];m@map:u@a=1;s@b=x y;e;").unwrap();
+
+	assert_eq!(message.get("list").unwrap().to_string(), "[[1,2],[]]");
+	assert_eq!(message.get("map").unwrap().to_string(), "u@a=1;s@b=x y;e");
+
+	// `[1,a]` would parse as neither kind of list.
+	let mixed = Value::List(vec![Value::Unsigned(1), Value::from("a")]);
+	assert_eq!(
+		mixed.to_string(),
+		"<no YADIL text: List elements must all have the same data type>"
+	);
+	let nested = Value::List(vec![Value::List(vec![Value::from("")])]);
+	assert_eq!(
+		nested.to_string(),
+		"<no YADIL text: Empty strings can't be written>"
+	);
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn canonicalizes_values() {
+	let canonical = |input: &[u8]| {
+		let mut message = yadil::parse(input).unwrap();
+		message.0.values_mut().for_each(Value::canonicalize);
+		message
+	};
+
+	let message = canonical(b"m@m:f@zero=-0.0;f@nan=nan;l.f@list=[-0.0];e;");
+	let mut twice = message.clone();
+	twice.0.values_mut().for_each(Value::canonicalize);
+	assert_eq!(yadil::to_bytes(&twice), yadil::to_bytes(&message));
+	assert_eq!(
+		yadil::to_bytes(&message).unwrap(),
+		b"m@m:l.f@list=[0.0];f@nan=nan;f@zero=0.0;e;"
+	);
+
+	let reordered = canonical(b"m@m:l.f@list=[0.0];f@zero=0.0;f@nan=nan;e;");
+	assert_eq!(yadil::to_bytes(&reordered), yadil::to_bytes(&message));
+}
+
+#[cfg(all(feature = "bytes", feature = "containers"))]
+#[test]
+fn flattens_bytes() {
+	let value = Value::List(vec![
+		Value::Byte(1),
+		Value::from("skipped"),
+		Value::List(vec![Value::Bytes(vec![2, 3]), Value::Unsigned(9)]),
+		Value::Bytes(vec![]),
+		Value::Byte(4),
+	]);
+	assert_eq!(value.flatten_bytes(), [1, 2, 3, 4]);
+	assert_eq!(
+		value.try_flatten_bytes().unwrap_err().kind,
+		ErrorKind::WrongValue
+	);
+
+	let bytes = Value::List(vec![Value::Byte(1), Value::Bytes(vec![2])]);
+	assert_eq!(bytes.try_flatten_bytes().unwrap(), [1, 2]);
+	assert_eq!(Value::Unsigned(1).flatten_bytes(), []);
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn applies_to_nested_values() {
+	let message = yadil::parse(b"m@m:u@a=1;l.l.u@list=[[2],[3, 4]];s@s=x;e;").unwrap();
+	let mut value = message.get("m").unwrap().clone();
+
+	value.apply(|value| {
+		if let Value::Unsigned(unsigned) = value {
+			*unsigned *= 2;
+		}
+	});
+
+	let doubled = yadil::parse(b"m@m:u@a=2;l.l.u@list=[[4],[6, 8]];s@s=x;e;").unwrap();
+	assert_eq!(Some(&value), doubled.get("m"));
+
+	// Parents are visited before their children, in order.
+	let mut list = Value::List(vec![
+		Value::List(vec![Value::Unsigned(1)]),
+		Value::List(vec![Value::Unsigned(2)]),
+	]);
+	let mut order = vec![];
+	list.apply(|value| order.push(value.to_string()));
+	assert_eq!(order, ["[[1],[2]]", "[1]", "1", "[2]", "2"]);
+}
+
+#[test]
+fn unifies_numbers() {
+	let message = yadil::parse(b"u@u=3;i@i=-3;f@f=2.5;f@whole=4.0;s@s=3;").unwrap();
+	let number = |key: &str| message.get(key).unwrap().as_number();
+
+	assert_eq!(number("u"), Some(Number::Unsigned(3)));
+	assert_eq!(number("i"), Some(Number::Signed(-3)));
+	assert_eq!(number("f"), Some(Number::Float(2.5)));
+	assert_eq!(number("s"), None);
+
+	assert_eq!(number("u").unwrap().to_f64(), 3.0);
+	assert_eq!(number("i").unwrap().to_f64(), -3.0);
+	assert_eq!(number("u").unwrap().to_i128(), Some(3));
+	assert_eq!(number("i").unwrap().to_i128(), Some(-3));
+	assert_eq!(number("whole").unwrap().to_i128(), Some(4));
+	assert_eq!(number("f").unwrap().to_i128(), None);
+	assert_eq!(Number::Float(f64::INFINITY).to_i128(), None);
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn builds_byte_values() {
+	assert_eq!(Value::byte(7), Value::Byte(7));
+	assert_eq!(Value::bytes(&b"ab"[..]), Value::Bytes(b"ab".to_vec()));
+	assert_eq!(Value::from(7u8), Value::byte(7));
+	assert_eq!(Value::from(vec![1u8, 2]), Value::bytes([1, 2]));
+
+	assert_eq!(Value::byte(7).as_byte(), Some(7));
+	assert_eq!(Value::bytes("ab").as_bytes(), Some(&b"ab"[..]));
+	assert_eq!(Value::byte(7).as_bytes(), None);
+	assert_eq!(Value::Unsigned(7).as_byte(), None);
+
+	let message = yadil::parse(b"y@y=0x41;x@x=[1, 2];").unwrap();
+	assert_eq!(message.get("y"), Some(&Value::byte(b'A')));
+	assert_eq!(message.get("x"), Some(&Value::bytes([1, 2])));
+}
+
+#[test]
+fn accessors_match_their_variant() {
+	assert_eq!(Value::Unsigned(5).as_str(), None);
+	assert_eq!(Value::Unsigned(5).as_unsigned(), Some(5));
+	assert_eq!(Value::Signed(5).as_unsigned(), None);
+	assert_eq!(Value::Signed(-5).as_signed(), Some(-5));
+	assert_eq!(Value::Float(0.5).as_float(), Some(0.5));
+	assert_eq!(Value::Unsigned(1).as_float(), None);
+	assert_eq!(Value::Bool(true).as_bool(), Some(true));
+	assert_eq!(Value::from("t").as_bool(), None);
+	assert_eq!(Value::from("text").as_str(), Some("text"));
+}
+
+#[cfg(feature = "containers")]
+#[test]
+fn container_accessors() {
+	let message = yadil::parse(b"l.u@list=[1];m@map:u@a=1;e;").unwrap();
+	let (list, map) = (message.get("list").unwrap(), message.get("map").unwrap());
+
+	assert_eq!(list.as_list(), Some(&[Value::Unsigned(1)][..]));
+	assert_eq!(list.as_map(), None);
+	assert_eq!(
+		map.as_map().unwrap().get(&b"a"[..]),
+		Some(&Value::Unsigned(1))
+	);
+	assert_eq!(map.as_list(), None);
+}
+
+#[test]
+fn compares_values() {
+	let parse = |input: &[u8]| yadil::parse(input).unwrap();
+
+	assert_eq!(parse(b"u@a=1;s@b=x;"), parse(b"s@b=x;u@a=1;"));
+	assert_ne!(parse(b"u@a=1;"), parse(b"u@a=2;"));
+	assert_ne!(parse(b"u@a=1;"), parse(b"i@a=1;"));
+	assert_ne!(parse(b"u@a=1;"), parse(b"u@b=1;"));
+
+	// Floats compare as `f64`s.
+	assert_eq!(Value::Float(-0.0), Value::Float(0.0));
+	assert_ne!(Value::Float(f64::NAN), Value::Float(f64::NAN));
+	assert_ne!(parse(b"f@a=nan;"), parse(b"f@a=nan;"));
+}